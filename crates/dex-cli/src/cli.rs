@@ -1,32 +1,53 @@
+use crate::error::CliError;
 use crate::utils::{
-    DEFAULT_CLIENT_TIMEOUT_SECS, check_file_existence, default_key_path, default_relays_path, get_valid_key_from_file,
-    get_valid_urls_from_file, write_into_stdout,
+    DEFAULT_CLIENT_TIMEOUT_SECS, add_urls_to_file, check_file_existence, default_compilers_path, default_key_path,
+    default_logger_config_path, default_oracles_path, default_registry_path, default_relays_path,
+    get_valid_key_from_file, get_valid_urls_from_file, remove_urls_from_file, write_into_stdout,
 };
-use clap::{Parser, Subcommand};
-use nostr::{EventId, PublicKey};
+use clap::{Args, Parser, Subcommand};
+use elements::{Address, AssetId, OutPoint, TxOut, TxOutWitness, confidential};
+use nostr::nips::nip65::RelayMetadata;
+use nostr::{Event, EventId, JsonUtil, PublicKey, RelayUrl, Timestamp};
 
+use dex_nostr_relay::archive::{self, ArchiveQuery};
+use dex_nostr_relay::batch::{LadderSpec, SizeDistribution};
+use dex_nostr_relay::compiler_provenance::CompilerAllowList;
+use dex_nostr_relay::inspection;
+use dex_nostr_relay::keeper::{KeeperSpec, MakerKeeper, OraclePriceSource};
+use dex_nostr_relay::maker_registry::{FileMakerRegistry, RpcMakerRegistry};
+use dex_nostr_relay::offline;
+use dex_nostr_relay::option_terms::{OptionStyle, OptionTerms, OptionTermsFilter, OptionType, Settlement};
+use dex_nostr_relay::oracle::{AttestationQuery, OracleSet, PriceAttestationTags};
+use dex_nostr_relay::query::QueryOptions;
 use dex_nostr_relay::relay_client::ClientConfig;
-use dex_nostr_relay::relay_processor::{OrderPlaceEventTags, OrderReplyEventTags, RelayProcessor};
+use dex_nostr_relay::relay_info::UnsupportedExpirationPolicy;
+use dex_nostr_relay::relay_processor::{OrderFilter, OrderPlaceEventTags, OrderReplyEventTags, RelayProcessor};
+use dex_nostr_relay::retry::RetryPolicy;
+use dex_nostr_relay::rfq::{QuoteRequestTags, QuoteTags, QuoteTerms};
+use dex_nostr_relay::settlement::{SettlementParams, SettlementRole};
+use dex_nostr_relay::swap::SwapInput;
+use dex_nostr_relay::types::MAKER_EXPIRATION_TIME;
+use global_utils::logger::LoggerConfig;
+use global_utils::metrics;
+use global_utils::secret::Secret;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 use tracing::instrument;
 
 #[derive(Parser)]
 pub struct Cli {
-    /// Specify private key for posting authorized events on Nostr Relay
-    #[arg(
-        short = 'k',
-        long,
-        value_parser = check_file_existence
-    )]
-    key_path: Option<PathBuf>,
-    /// Specify file with list of relays to use
-    #[arg(
-        short = 'r',
-        long,
-        value_parser = check_file_existence
-    )]
-    relays_path: Option<PathBuf>,
+    #[command(flatten)]
+    connection: ConnectionArgs,
+    /// Specify TOML file with logger settings: levels per target, format and rotating log file
+    #[arg(long, value_parser = check_file_existence)]
+    log_config: Option<PathBuf>,
+    /// Keep stdout free of logs, so command output can be piped into other tools
+    #[arg(long)]
+    machine_output: bool,
+    /// Serve Prometheus metrics of relay and order operations on the given local address, e.g. 127.0.0.1:9464
+    #[arg(long)]
+    metrics_addr: Option<SocketAddr>,
     #[command(subcommand)]
     command: Command,
 }
@@ -43,17 +64,119 @@ enum Command {
         #[command(subcommand)]
         action: TakerCommands,
     },
+    #[command(about = "Commands collection for price oracles")]
+    Oracle {
+        #[command(subcommand)]
+        action: OracleCommands,
+    },
+    #[command(about = "Commands collection for the relays used by client")]
+    Relays {
+        #[command(subcommand)]
+        action: RelaysCommands,
+    },
     #[command(about = "Get replies for a specific order by its ID [no authentication required]")]
     GetOrderReplies {
         #[arg(short = 'i', long)]
         event_id: EventId,
+        #[command(flatten)]
+        query: QueryArgs,
     },
     #[command(about = "List available orders from relays [no authentication required]")]
-    ListOrders,
+    ListOrders {
+        #[arg(
+            long,
+            help = "Also fetch from relays the maker publishes to (NIP-65), pubkey in bech32 or hex format"
+        )]
+        discover_relays_of: Option<PublicKey>,
+        #[arg(long, help = "Hide orders with NIP-13 proof-of-work below the given difficulty")]
+        min_pow: Option<u8>,
+        #[arg(
+            long,
+            help = "Show only orders from makers registered in the Maker Identity Registry"
+        )]
+        registered_only: bool,
+        #[arg(long, help = "Show maker reputation derived from trade history next to each order")]
+        reputation: bool,
+        #[arg(
+            long,
+            requires = "reputation",
+            help = "Weight reputation by follow list of the pubkey (web-of-trust), pubkey in bech32 or hex format"
+        )]
+        wot: Option<PublicKey>,
+        #[arg(long, help = "Hide orders compiled by unknown or blacklisted Simplicity compilers")]
+        trusted_compilers_only: bool,
+        #[arg(long, help = "Show only option orders")]
+        options_only: bool,
+        #[arg(long, help = "Show only option orders of the given type: 'call' or 'put'")]
+        option_type: Option<OptionType>,
+        #[arg(long, help = "Show only option orders of the given style: 'european' or 'american'")]
+        option_style: Option<OptionStyle>,
+        #[arg(long, help = "Show only option orders with strike price not below the given one")]
+        min_strike: Option<u64>,
+        #[arg(long, help = "Show only option orders with strike price not above the given one")]
+        max_strike: Option<u64>,
+        #[arg(
+            long,
+            help = "Show only option orders settling not later than the given unix timestamp"
+        )]
+        settles_before: Option<u64>,
+        #[arg(long, help = "Show only option orders with the given collateral asset id")]
+        collateral_asset: Option<String>,
+        #[arg(long, help = "Show only option orders with the given settlement asset id")]
+        settlement_asset: Option<String>,
+        #[command(flatten)]
+        query: QueryArgs,
+    },
     #[command(about = "Get events by its ID [no authentication required]")]
     GetEventsById {
         #[arg(short = 'i', long)]
         event_id: EventId,
+        #[command(flatten)]
+        query: QueryArgs,
+    },
+    #[command(about = "Verify event and decode it according to the DEX protocol [no authentication required]")]
+    Inspect {
+        #[arg(short = 'i', long, conflicts_with = "file", help = "Fetch event from relays")]
+        event_id: Option<EventId>,
+        #[arg(
+            short = 'f',
+            long,
+            help = "Read event JSON from the file, '-' or no event id reads stdin"
+        )]
+        file: Option<PathBuf>,
+    },
+    #[command(about = "Export DEX events matching the query into NDJSON file [no authentication required]")]
+    Export {
+        #[arg(short = 'o', long, help = "File to write NDJSON archive into")]
+        output: PathBuf,
+        #[arg(
+            long,
+            help = "Export only events of the given authors, pubkey in bech32 or hex format"
+        )]
+        author: Vec<PublicKey>,
+        #[arg(long, help = "Export only events created since the given unix timestamp")]
+        since: Option<u64>,
+        #[arg(long, help = "Export only events created until the given unix timestamp")]
+        until: Option<u64>,
+    },
+    #[command(
+        about = "Verify NDJSON archive and re-publish it to the relays used by client [no authentication required]"
+    )]
+    Import {
+        #[arg(short = 'i', long, value_parser = check_file_existence, help = "File with NDJSON archive")]
+        input: PathBuf,
+    },
+    #[command(about = "Sign exported order or reply offline, relays are not contacted [authentication required]")]
+    SignEvent {
+        #[arg(short = 'i', long, value_parser = check_file_existence, help = "File with unsigned event JSON")]
+        input: PathBuf,
+        #[arg(short = 'o', long, help = "File to write signed event JSON into")]
+        output: PathBuf,
+    },
+    #[command(about = "Verify and publish order or reply signed offline [no authentication required]")]
+    PublishEvent {
+        #[arg(short = 'i', long, value_parser = check_file_existence, help = "File with signed event JSON")]
+        input: PathBuf,
     },
 }
 
@@ -67,12 +190,133 @@ enum MakerCommands {
         asset_to_buy: String,
         #[arg(short = 'p', long, default_value = "0")]
         price: u64,
+        #[arg(short = 'a', long, help = "Amount of asset to sell offered by the order")]
+        amount: Option<u64>,
         #[arg(short = 'e', long, default_value = "0")]
         expiry: u64,
-        #[arg(short = 'c', long, default_value = "")]
+        #[arg(
+            short = 'c',
+            long,
+            help = "Version of the Simplicity compiler of the order contract, e.g. 'simplicity-v1.2.3', must not be empty"
+        )]
         compiler_name: String,
-        #[arg(short = 's', long, default_value = "")]
+        #[arg(
+            long,
+            help = "Hex encoded deterministic build hash of the compiler, required for placement"
+        )]
         compiler_build_hash: String,
+        #[arg(long, help = "Base64 encoded Simplicity program of the order contract")]
+        contract: Option<String>,
+        #[arg(long, help = "Hex encoded CMR of the order contract")]
+        contract_cmr: Option<String>,
+        #[arg(long, help = "Liquid address of the contract output")]
+        contract_address: Option<String>,
+        #[command(flatten)]
+        option: Box<OptionOrderArgs>,
+        #[arg(
+            long,
+            requires = "signer_pubkey",
+            help = "Write unsigned order JSON into the file for offline signing instead of publishing"
+        )]
+        export_unsigned: Option<PathBuf>,
+        #[arg(long, help = "Pubkey of the offline signer in bech32 or hex format")]
+        signer_pubkey: Option<PublicKey>,
+        #[arg(
+            long,
+            requires = "export_unsigned",
+            help = "Lifetime of the exported order in seconds, leave time for offline signing"
+        )]
+        expires_in: Option<u64>,
+    },
+    #[command(about = "Create ladder of orders across the price range as Maker [authentication required]")]
+    Ladder {
+        #[arg(short = 's', long)]
+        asset_to_sell: String,
+        #[arg(short = 'b', long)]
+        asset_to_buy: String,
+        #[arg(long)]
+        min_price: u64,
+        #[arg(long)]
+        max_price: u64,
+        #[arg(short = 'n', long, help = "Amount of orders in the ladder")]
+        orders: usize,
+        #[arg(short = 'a', long, help = "Amount of asset to sell shared by all orders")]
+        total_amount: u64,
+        #[arg(
+            long,
+            default_value = "uniform",
+            help = "Order sizes from the lowest price: 'uniform', 'increasing' or 'decreasing'"
+        )]
+        distribution: SizeDistribution,
+        #[arg(
+            short = 'c',
+            long,
+            help = "Version of the Simplicity compiler of the order contract, e.g. 'simplicity-v1.2.3', must not be empty"
+        )]
+        compiler_name: String,
+        #[arg(
+            long,
+            help = "Hex encoded deterministic build hash of the compiler, required for placement"
+        )]
+        compiler_build_hash: String,
+        #[arg(long, help = "Cancel already placed orders if any order of the ladder fails")]
+        all_or_nothing: bool,
+    },
+    #[command(about = "Keep orders from the spec alive until interrupted as Maker [authentication required]")]
+    Keep {
+        #[arg(long, value_parser = check_file_existence, help = "TOML file with order templates")]
+        spec: PathBuf,
+        #[arg(long, help = "Reprice templates with `reprice = true` from the oracle median price")]
+        oracle_reprice: bool,
+        #[arg(
+            long,
+            default_value = "0",
+            requires = "oracle_reprice",
+            help = "Markup over the oracle price in basis points"
+        )]
+        spread_bps: u32,
+        #[arg(long, default_value = "600", help = "Accepted age of oracle attestations in seconds")]
+        oracle_tolerance: u64,
+    },
+    #[command(about = "Cancel own orders as Maker [authentication required]")]
+    CancelOrders {
+        #[arg(short = 'i', long, num_args = 1.., required = true)]
+        event_ids: Vec<EventId>,
+    },
+    #[command(about = "Settle matched option after expiry as Maker [authentication required]")]
+    Settle {
+        #[command(flatten)]
+        settlement: Box<SettlementArgs>,
+    },
+    #[command(about = "Validate swap PSET proposed by taker reply as Maker [no authentication required]")]
+    InspectReply {
+        #[arg(short = 'i', long)]
+        reply_event_id: EventId,
+        #[arg(long, help = "Liquid address of the maker inputs and outputs in the swap")]
+        maker_address: Address,
+    },
+    #[command(about = "Accept taker reply on own order as Maker [authentication required]")]
+    AcceptReply {
+        #[arg(short = 'i', long)]
+        reply_event_id: EventId,
+    },
+    #[command(about = "Respond to request for quote as Maker [authentication required]")]
+    Quote {
+        #[arg(short = 'i', long)]
+        request_event_id: EventId,
+        #[arg(short = 'p', long, help = "Price in asset to sell per unit of asset to buy")]
+        price: u64,
+        #[arg(short = 'a', long, help = "Quoted amount of asset to buy")]
+        amount: u64,
+        #[arg(long, default_value = "300", help = "Quote lifetime in seconds")]
+        expires_in: u64,
+        #[arg(long, help = "Encrypt quote terms to the requester (NIP-44)")]
+        encrypt: bool,
+    },
+    #[command(about = "List active requests for quote [no authentication required]")]
+    ListQuoteRequests {
+        #[command(flatten)]
+        query: QueryArgs,
     },
 }
 
@@ -84,16 +328,212 @@ enum TakerCommands {
         maker_event_id: EventId,
         #[arg(short = 'p', long, help = " Pubkey in bech32 or hex format")]
         maker_pubkey: PublicKey,
-        #[arg(short = 't', long, help = "Txid from funding transaction step", required = false)]
-        tx_id: String,
+        #[arg(
+            short = 't',
+            long,
+            help = "Txid from funding transaction step",
+            required_unless_present = "pset"
+        )]
+        tx_id: Option<String>,
+        #[arg(long, help = "Base64 encoded PSET proposing atomic swap of the order assets")]
+        pset: Option<String>,
+        #[arg(long, help = "Also send reply to relays the maker reads from (NIP-65)")]
+        discover_relays: bool,
+        #[arg(
+            long,
+            requires = "signer_pubkey",
+            help = "Write unsigned reply JSON into the file for offline signing instead of publishing"
+        )]
+        export_unsigned: Option<PathBuf>,
+        #[arg(long, help = "Pubkey of the offline signer in bech32 or hex format")]
+        signer_pubkey: Option<PublicKey>,
+    },
+    #[command(about = "Exercise bought option as Taker [authentication required]")]
+    Exercise {
+        #[command(flatten)]
+        settlement: Box<SettlementArgs>,
+    },
+    #[command(about = "Verify Simplicity contract of the order before funding [no authentication required]")]
+    InspectOrder {
+        #[arg(short = 'i', long)]
+        maker_event_id: EventId,
+    },
+    #[command(about = "Broadcast request for quote as Taker [authentication required]")]
+    RequestQuote {
+        #[arg(short = 's', long)]
+        asset_to_sell: String,
+        #[arg(short = 'b', long)]
+        asset_to_buy: String,
+        #[arg(short = 'a', long, help = "Requested amount of asset to buy")]
+        amount: u64,
+        #[arg(long, default_value = "300", help = "Request lifetime in seconds")]
+        expires_in: u64,
+    },
+    #[command(about = "List active quotes on the request, encrypted quotes require authentication as requester")]
+    ListQuotes {
+        #[arg(short = 'i', long)]
+        request_event_id: EventId,
+        #[command(flatten)]
+        query: QueryArgs,
     },
 }
 
-impl Cli {
-    #[instrument(skip(self))]
-    pub async fn process(self) -> crate::error::Result<()> {
+#[derive(Debug, Subcommand)]
+enum OracleCommands {
+    #[command(about = "Publish signed price attestation as Oracle [authentication required]")]
+    Attest {
+        #[arg(short = 'b', long)]
+        base_asset: String,
+        #[arg(short = 'q', long)]
+        quote_asset: String,
+        #[arg(short = 'p', long, help = "Price of the base asset in quote asset units")]
+        price: u64,
+        #[arg(
+            short = 't',
+            long,
+            help = "Unix timestamp of the observation, current time by default"
+        )]
+        timestamp: Option<u64>,
+    },
+    #[command(about = "Get median price attested by trusted oracles [no authentication required]")]
+    GetPrice {
+        #[arg(short = 'b', long)]
+        base_asset: String,
+        #[arg(short = 'q', long)]
+        quote_asset: String,
+        #[arg(short = 't', long, help = "Unix timestamp of the price, current time by default")]
+        timestamp: Option<u64>,
+        #[arg(
+            long,
+            default_value = "600",
+            help = "Accepted distance from the timestamp in seconds"
+        )]
+        tolerance: u64,
+    },
+}
+
+#[derive(Debug, Args)]
+struct ConnectionArgs {
+    /// Specify private key for posting authorized events on Nostr Relay
+    #[arg(
+        short = 'k',
+        long,
+        value_parser = check_file_existence
+    )]
+    key_path: Option<PathBuf>,
+    /// Specify file with list of relays to use, `relays add` creates it if missing
+    #[arg(short = 'r', long)]
+    relays_path: Option<PathBuf>,
+    /// Specify file with public keys of makers registered in the Maker Identity Registry
+    #[arg(long, value_parser = check_file_existence, conflicts_with = "registry_rpc")]
+    registry_path: Option<PathBuf>,
+    /// Specify JSON-RPC endpoint of the Maker Identity Registry
+    #[arg(long)]
+    registry_rpc: Option<String>,
+    /// Specify TOML file with trusted and blacklisted Simplicity compilers
+    #[arg(long, value_parser = check_file_existence)]
+    compilers_path: Option<PathBuf>,
+    /// Specify file with public keys of trusted price oracles
+    #[arg(long, value_parser = check_file_existence)]
+    oracles_path: Option<PathBuf>,
+    /// Minimum amount of oracles which must attest a price
+    #[arg(long, default_value = "1")]
+    oracle_threshold: usize,
+    /// Minimum amount of healthy relays required before publishing
+    #[arg(long, default_value = "1")]
+    min_healthy_relays: usize,
+    /// Do not publish orders to relays which do not advertise expiration support (NIP-40)
+    #[arg(long)]
+    skip_relays_without_expiration: bool,
+    /// Mine NIP-13 proof-of-work with the given difficulty for published orders and replies
+    #[arg(long, default_value = "0")]
+    pow: u8,
+    /// Attempts of relay operations failed with transient errors, `1` disables retries
+    #[arg(long, default_value = "3")]
+    retry_attempts: usize,
+    /// Time budget in seconds of a relay operation including its retries
+    #[arg(long, default_value = "30")]
+    retry_deadline: u64,
+}
+
+#[derive(Debug, Args)]
+struct QueryArgs {
+    #[arg(long, help = "Fetch only events created since the given unix timestamp")]
+    since: Option<u64>,
+    #[arg(
+        long,
+        alias = "before",
+        help = "Fetch only events created at or before the given unix timestamp"
+    )]
+    until: Option<u64>,
+    #[arg(long, help = "Fetch only events of the given author, may be repeated")]
+    author: Vec<PublicKey>,
+    #[arg(long, help = "Show at most the given amount of events, newest first")]
+    limit: Option<usize>,
+    #[arg(long, help = "Also show events whose expiration has passed")]
+    include_expired: bool,
+}
+
+#[derive(Debug, Args)]
+struct SettlementArgs {
+    #[arg(short = 'i', long, help = "Taker reply which funded the order")]
+    reply_event_id: EventId,
+    #[arg(
+        long,
+        default_value = "0",
+        help = "Output of the funding transaction locked by the contract"
+    )]
+    vout: u32,
+    #[arg(long, help = "Amount of collateral locked by the contract")]
+    collateral_amount: u64,
+    #[arg(long, help = "Liquid address receiving the maker share")]
+    maker_address: Address,
+    #[arg(long, help = "Liquid address receiving the taker share")]
+    taker_address: Address,
+    #[arg(
+        long,
+        default_value = "0",
+        requires_all = ["fee_outpoint", "fee_input_amount"],
+        help = "Fee paid by the settling party from the fee input"
+    )]
+    fee: u64,
+    #[arg(
+        long,
+        default_value_t = AssetId::LIQUID_BTC,
+        help = "Policy asset of the network the fee is paid in"
+    )]
+    fee_asset: AssetId,
+    #[arg(
+        long,
+        help = "Outpoint 'txid:vout' funding the fee, owned by the address of the settling party"
+    )]
+    fee_outpoint: Option<OutPoint>,
+    #[arg(
+        long,
+        help = "Explicit amount of the fee asset in the fee outpoint, change returns to the settling party"
+    )]
+    fee_input_amount: Option<u64>,
+    #[arg(
+        long,
+        default_value = "600",
+        help = "Accepted distance between oracle attestation and settlement time in seconds"
+    )]
+    oracle_tolerance: u64,
+}
+
+impl ConnectionArgs {
+    fn key_path(&self) -> PathBuf {
+        self.key_path.clone().unwrap_or(default_key_path())
+    }
+
+    fn relays_path(&self) -> PathBuf {
+        self.relays_path.clone().unwrap_or(default_relays_path())
+    }
+
+    /// Connects to relays from the relays file.
+    async fn connect(&self) -> crate::error::Result<RelayProcessor> {
         let keys = {
-            match get_valid_key_from_file(&self.key_path.unwrap_or(default_key_path())) {
+            match get_valid_key_from_file(&self.key_path()) {
                 Ok(keys) => Some(keys),
                 Err(err) => {
                     tracing::warn!("Failed to parse key, {err}");
@@ -101,64 +541,642 @@ impl Cli {
                 }
             }
         };
-        let relays_urls = get_valid_urls_from_file(&self.relays_path.unwrap_or(default_relays_path()))?;
+        let relays_urls = get_valid_urls_from_file(&self.relays_path())?;
         let relay_processor = RelayProcessor::try_from_config(
             relays_urls,
-            keys,
+            keys.map(Secret::into_inner),
             ClientConfig {
                 timeout: Duration::from_secs(DEFAULT_CLIENT_TIMEOUT_SECS),
+                min_healthy_relays: self.min_healthy_relays,
+                unsupported_expiration_policy: if self.skip_relays_without_expiration {
+                    UnsupportedExpirationPolicy::Skip
+                } else {
+                    UnsupportedExpirationPolicy::Warn
+                },
+                pow_difficulty: self.pow,
+                retry_policy: RetryPolicy {
+                    max_attempts: self.retry_attempts.max(1),
+                    deadline: Duration::from_secs(self.retry_deadline),
+                    ..Default::default()
+                },
+                ..Default::default()
             },
         )
         .await?;
 
+        Ok(relay_processor)
+    }
+
+    /// Attaches the oracle set, if configured, to the processor.
+    fn with_oracle_set(&self, relay_processor: RelayProcessor) -> crate::error::Result<RelayProcessor> {
+        let oracles_path = self.oracles_path.clone().unwrap_or(default_oracles_path());
+        Ok(if oracles_path.is_file() {
+            relay_processor.with_oracle_set(OracleSet::from_file(oracles_path, self.oracle_threshold)?)
+        } else {
+            relay_processor
+        })
+    }
+
+    /// Attaches the compiler allow-list, if configured, to the processor.
+    fn with_compiler_allow_list(&self, relay_processor: RelayProcessor) -> crate::error::Result<RelayProcessor> {
+        let compilers_path = self.compilers_path.clone().unwrap_or(default_compilers_path());
+        Ok(if compilers_path.is_file() {
+            relay_processor.with_compiler_allow_list(CompilerAllowList::from_file(compilers_path)?)
+        } else {
+            relay_processor
+        })
+    }
+
+    /// Attaches the Maker Identity Registry, if configured, to the processor.
+    fn with_maker_registry(&self, relay_processor: RelayProcessor) -> crate::error::Result<RelayProcessor> {
+        Ok(match (&self.registry_rpc, &self.registry_path) {
+            (Some(endpoint), _) => relay_processor.with_maker_registry(RpcMakerRegistry::new(
+                endpoint.clone(),
+                Duration::from_secs(DEFAULT_CLIENT_TIMEOUT_SECS),
+            )),
+            (None, Some(path)) => relay_processor.with_maker_registry(FileMakerRegistry::from_file(path)?),
+            (None, None) if default_registry_path().is_file() => {
+                relay_processor.with_maker_registry(FileMakerRegistry::from_file(default_registry_path())?)
+            }
+            (None, None) => relay_processor,
+        })
+    }
+}
+
+impl QueryArgs {
+    fn into_options(self) -> QueryOptions {
+        QueryOptions {
+            since: self.since.map(Timestamp::from),
+            until: self.until.map(Timestamp::from),
+            authors: (!self.author.is_empty()).then(|| self.author.into_iter().collect()),
+            limit: self.limit,
+            include_expired: self.include_expired,
+        }
+    }
+}
+
+impl SettlementArgs {
+    fn into_params(self, role: SettlementRole) -> (EventId, SettlementParams) {
+        let settling_address = match role {
+            SettlementRole::Maker => &self.maker_address,
+            SettlementRole::Taker => &self.taker_address,
+        };
+        let fee_input = self
+            .fee_outpoint
+            .zip(self.fee_input_amount)
+            .map(|(outpoint, amount)| SwapInput {
+                outpoint,
+                utxo: TxOut {
+                    asset: confidential::Asset::Explicit(self.fee_asset),
+                    value: confidential::Value::Explicit(amount),
+                    nonce: confidential::Nonce::Null,
+                    script_pubkey: settling_address.script_pubkey(),
+                    witness: TxOutWitness::default(),
+                },
+            });
+
+        (
+            self.reply_event_id,
+            SettlementParams {
+                contract_vout: self.vout,
+                collateral_amount: self.collateral_amount,
+                maker_script: self.maker_address.script_pubkey(),
+                taker_script: self.taker_address.script_pubkey(),
+                fee: self.fee,
+                fee_asset: self.fee_asset,
+                fee_input,
+                oracle_tolerance: Duration::from_secs(self.oracle_tolerance),
+            },
+        )
+    }
+}
+
+#[derive(Debug, Args)]
+struct OptionOrderArgs {
+    #[arg(
+        long,
+        help = "Offer option contract of the given type: 'call' or 'put'",
+        requires_all = ["strike_price", "collateral_asset", "settlement_asset"]
+    )]
+    option_type: Option<OptionType>,
+    #[arg(
+        long,
+        default_value = "european",
+        help = "Option exercise style: 'european' or 'american'"
+    )]
+    option_style: OptionStyle,
+    #[arg(long, help = "Option strike price in settlement asset per contract")]
+    strike_price: Option<u64>,
+    #[arg(
+        long,
+        conflicts_with = "settlement_time",
+        requires = "option_type",
+        help = "Option settlement Liquid block height"
+    )]
+    settlement_height: Option<u32>,
+    #[arg(long, requires = "option_type", help = "Option settlement unix timestamp")]
+    settlement_time: Option<u64>,
+    #[arg(long, help = "Option collateral asset id")]
+    collateral_asset: Option<String>,
+    #[arg(long, help = "Option settlement asset id")]
+    settlement_asset: Option<String>,
+}
+
+impl OptionOrderArgs {
+    fn into_terms(self) -> Option<OptionTerms> {
+        self.option_type.map(|option_type| OptionTerms {
+            option_type,
+            style: self.option_style,
+            strike_price: self.strike_price.unwrap_or_default(),
+            settlement: match self.settlement_height {
+                Some(height) => Settlement::Height(height),
+                None => Settlement::Time(self.settlement_time.unwrap_or_default()),
+            },
+            collateral_asset: self.collateral_asset.unwrap_or_default(),
+            settlement_asset: self.settlement_asset.unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum RelaysCommands {
+    #[command(about = "Probe relays and show their health status [no authentication required]")]
+    Status,
+    #[command(about = "Show relays information documents (NIP-11) [no authentication required]")]
+    Info,
+    #[command(about = "List relays from the relays file")]
+    List,
+    #[command(about = "Add relays to the relays file")]
+    Add {
+        #[arg(required = true)]
+        urls: Vec<RelayUrl>,
+    },
+    #[command(about = "Remove relays from the relays file")]
+    Remove {
+        #[arg(required = true)]
+        urls: Vec<RelayUrl>,
+    },
+    #[command(about = "Discover relays of the user from its relay list (NIP-65) [no authentication required]")]
+    Discover {
+        #[arg(short = 'p', long, help = "Pubkey in bech32 or hex format")]
+        pubkey: PublicKey,
+        #[arg(long, help = "Add discovered relays to the relays file")]
+        save: bool,
+    },
+}
+
+impl Cli {
+    /// Logger settings from the config file, if present, adjusted by command line flags.
+    pub fn logger_config(&self) -> crate::error::Result<LoggerConfig> {
+        let log_config_path = self.log_config.clone().unwrap_or(default_logger_config_path());
+        let mut logger_config = if log_config_path.is_file() {
+            LoggerConfig::from_file(log_config_path)?
+        } else {
+            LoggerConfig::default()
+        };
+        logger_config.machine_output |= self.machine_output;
+
+        Ok(logger_config)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn process(self) -> crate::error::Result<()> {
+        if let Some(metrics_addr) = self.metrics_addr {
+            metrics::spawn_exporter(metrics_addr)?;
+        }
+
+        let connection = self.connection;
+
         let msg = {
             match self.command {
-                Command::Maker { action } => match action {
-                    MakerCommands::CreateOrder {
-                        asset_to_sell,
-                        asset_to_buy,
-                        price,
-                        expiry,
-                        compiler_name,
-                        compiler_build_hash,
-                    } => {
-                        let res = relay_processor
-                            .place_order(OrderPlaceEventTags {
+                Command::Maker { action } => {
+                    let relay_processor = connection.connect().await?;
+                    match action {
+                        MakerCommands::CreateOrder {
+                            asset_to_sell,
+                            asset_to_buy,
+                            price,
+                            amount,
+                            expiry,
+                            compiler_name,
+                            compiler_build_hash,
+                            contract,
+                            contract_cmr,
+                            contract_address,
+                            option,
+                            export_unsigned,
+                            signer_pubkey,
+                            expires_in,
+                        } => {
+                            let tags = OrderPlaceEventTags {
                                 asset_to_sell,
                                 asset_to_buy,
                                 price,
                                 expiry,
                                 compiler_name,
                                 compiler_build_hash,
-                            })
-                            .await?;
-                        format!("Creating order result: {res:#?}")
+                                contract_program: contract,
+                                contract_cmr,
+                                contract_address,
+                                amount,
+                                option_terms: option.into_terms(),
+                            };
+                            match (export_unsigned, signer_pubkey) {
+                                (Some(path), Some(signer_pubkey)) => {
+                                    let expires_in = Duration::from_secs(expires_in.unwrap_or(MAKER_EXPIRATION_TIME));
+                                    let mut unsigned =
+                                        relay_processor.build_order(tags, signer_pubkey, expires_in).await?;
+                                    unsigned.ensure_id();
+                                    std::fs::write(&path, unsigned.as_json())?;
+                                    format!("Unsigned order written into {}", path.display())
+                                }
+                                _ => {
+                                    let res = relay_processor.place_order(tags).await?;
+                                    format!("Creating order result: {res:#?}")
+                                }
+                            }
+                        }
+                        MakerCommands::Ladder {
+                            asset_to_sell,
+                            asset_to_buy,
+                            min_price,
+                            max_price,
+                            orders,
+                            total_amount,
+                            distribution,
+                            compiler_name,
+                            compiler_build_hash,
+                            all_or_nothing,
+                        } => {
+                            let orders = LadderSpec {
+                                asset_to_sell,
+                                asset_to_buy,
+                                min_price,
+                                max_price,
+                                orders,
+                                total_amount,
+                                distribution,
+                                compiler_name,
+                                compiler_build_hash,
+                            }
+                            .generate()?;
+                            let res = if all_or_nothing {
+                                relay_processor.place_orders_all_or_nothing(orders).await?
+                            } else {
+                                relay_processor.place_orders(orders).await?
+                            };
+                            format!("Creating ladder result: {res:#?}")
+                        }
+                        MakerCommands::Keep {
+                            spec,
+                            oracle_reprice,
+                            spread_bps,
+                            oracle_tolerance,
+                        } => {
+                            let relay_processor = if oracle_reprice {
+                                connection.with_oracle_set(relay_processor)?
+                            } else {
+                                relay_processor
+                            };
+                            let mut keeper = MakerKeeper::new(&relay_processor, KeeperSpec::from_file(spec)?);
+                            if oracle_reprice {
+                                keeper = keeper.with_price_source(OraclePriceSource {
+                                    tolerance: Duration::from_secs(oracle_tolerance),
+                                    spread_bps,
+                                });
+                            }
+                            keeper
+                                .run(async {
+                                    if let Err(err) = tokio::signal::ctrl_c().await {
+                                        tracing::error!("Failed to listen for shutdown signal: {err}");
+                                    }
+                                })
+                                .await?;
+                            "Maker keeper stopped, live orders are cancelled".to_string()
+                        }
+                        MakerCommands::CancelOrders { event_ids } => {
+                            let res = relay_processor.cancel_orders(event_ids).await?;
+                            format!("Cancelling orders result: {res:#?}")
+                        }
+                        MakerCommands::Settle { settlement } => {
+                            let relay_processor = connection.with_oracle_set(relay_processor)?;
+                            let (reply_event_id, params) = settlement.into_params(SettlementRole::Maker);
+                            let res = relay_processor
+                                .settle_order(SettlementRole::Maker, reply_event_id, params)
+                                .await?;
+                            format!("Settling order result: {res:#?}")
+                        }
+                        MakerCommands::InspectReply {
+                            reply_event_id,
+                            maker_address,
+                        } => {
+                            let res = relay_processor
+                                .inspect_reply(reply_event_id, &maker_address.script_pubkey())
+                                .await?;
+                            format!("Swap proposed by reply '{reply_event_id}': {res:#?}")
+                        }
+                        MakerCommands::AcceptReply { reply_event_id } => {
+                            let res = relay_processor.accept_reply(reply_event_id).await?;
+                            format!("Accepting reply result: {res:#?}")
+                        }
+                        MakerCommands::Quote {
+                            request_event_id,
+                            price,
+                            amount,
+                            expires_in,
+                            encrypt,
+                        } => {
+                            let res = relay_processor
+                                .quote(
+                                    request_event_id,
+                                    QuoteTags {
+                                        terms: QuoteTerms { price, amount },
+                                        expires_in: Duration::from_secs(expires_in),
+                                        encrypted: encrypt,
+                                    },
+                                )
+                                .await?;
+                            format!("Quoting result: {res:#?}")
+                        }
+                        MakerCommands::ListQuoteRequests { query } => {
+                            let res = relay_processor.list_quote_requests(&query.into_options()).await?;
+                            format!("List of quote requests: {res:#?}")
+                        }
                     }
-                },
-                Command::Taker { action } => match action {
-                    TakerCommands::ReplyOrder {
-                        maker_event_id,
-                        maker_pubkey,
-                        tx_id,
-                    } => {
-                        let res = relay_processor
-                            .reply_order(maker_event_id, maker_pubkey, OrderReplyEventTags { tx_id })
-                            .await?;
-                        format!("Replying order result: {res:#?}")
+                }
+                Command::Taker { action } => {
+                    let relay_processor = connection.connect().await?;
+                    match action {
+                        TakerCommands::ReplyOrder {
+                            maker_event_id,
+                            maker_pubkey,
+                            tx_id,
+                            pset,
+                            discover_relays,
+                            export_unsigned,
+                            signer_pubkey,
+                        } => {
+                            if discover_relays {
+                                let added = relay_processor
+                                    .use_relay_list(maker_pubkey, RelayMetadata::Read)
+                                    .await?;
+                                tracing::info!("Added maker relays: {added:?}");
+                            }
+                            let tags = OrderReplyEventTags {
+                                tx_id: tx_id.unwrap_or_default(),
+                                swap_pset: pset,
+                            };
+                            match (export_unsigned, signer_pubkey) {
+                                (Some(path), Some(signer_pubkey)) => {
+                                    let mut unsigned = relay_processor
+                                        .build_reply(maker_event_id, maker_pubkey, tags, signer_pubkey)
+                                        .await?;
+                                    unsigned.ensure_id();
+                                    std::fs::write(&path, unsigned.as_json())?;
+                                    format!("Unsigned reply written into {}", path.display())
+                                }
+                                _ => {
+                                    let res = relay_processor.reply_order(maker_event_id, maker_pubkey, tags).await?;
+                                    format!("Replying order result: {res:#?}")
+                                }
+                            }
+                        }
+                        TakerCommands::Exercise { settlement } => {
+                            let relay_processor = connection.with_oracle_set(relay_processor)?;
+                            let (reply_event_id, params) = settlement.into_params(SettlementRole::Taker);
+                            let res = relay_processor
+                                .settle_order(SettlementRole::Taker, reply_event_id, params)
+                                .await?;
+                            format!("Exercising order result: {res:#?}")
+                        }
+                        TakerCommands::InspectOrder { maker_event_id } => {
+                            let res = relay_processor.inspect_order(maker_event_id).await?;
+                            format!(
+                                "Order '{maker_event_id}'\nMaker: {}\nSell: {}\nBuy: {}\nPrice: {}\nCompiler: {} ({})\n{}",
+                                res.event.pubkey,
+                                res.tags.asset_to_sell,
+                                res.tags.asset_to_buy,
+                                res.tags.price,
+                                res.tags.compiler_name,
+                                res.tags.compiler_build_hash,
+                                res.contract
+                            )
+                        }
+                        TakerCommands::RequestQuote {
+                            asset_to_sell,
+                            asset_to_buy,
+                            amount,
+                            expires_in,
+                        } => {
+                            let res = relay_processor
+                                .request_quote(QuoteRequestTags {
+                                    asset_to_sell,
+                                    asset_to_buy,
+                                    amount,
+                                    expires_in: Duration::from_secs(expires_in),
+                                })
+                                .await?;
+                            format!("Requesting quote result: {res:#?}")
+                        }
+                        TakerCommands::ListQuotes {
+                            request_event_id,
+                            query,
+                        } => {
+                            let res = relay_processor
+                                .list_quotes(request_event_id, &query.into_options())
+                                .await?;
+                            format!("List of quotes: {res:#?}")
+                        }
+                    }
+                }
+                Command::Oracle { action } => {
+                    let relay_processor = connection.connect().await?;
+                    match action {
+                        OracleCommands::Attest {
+                            base_asset,
+                            quote_asset,
+                            price,
+                            timestamp,
+                        } => {
+                            let res = relay_processor
+                                .publish_price_attestation(PriceAttestationTags {
+                                    base_asset,
+                                    quote_asset,
+                                    price,
+                                    timestamp: timestamp.map_or_else(Timestamp::now, Timestamp::from),
+                                })
+                                .await?;
+                            format!("Publishing attestation result: {res:#?}")
+                        }
+                        OracleCommands::GetPrice {
+                            base_asset,
+                            quote_asset,
+                            timestamp,
+                            tolerance,
+                        } => {
+                            let relay_processor = connection.with_oracle_set(relay_processor)?;
+                            let res = relay_processor
+                                .get_oracle_price(&AttestationQuery {
+                                    base_asset,
+                                    quote_asset,
+                                    timestamp: timestamp.map_or_else(Timestamp::now, Timestamp::from),
+                                    tolerance: Duration::from_secs(tolerance),
+                                })
+                                .await?;
+                            format!("Oracle price: {res:#?}")
+                        }
+                    }
+                }
+                Command::Relays { action } => match action {
+                    RelaysCommands::List => {
+                        let res = get_valid_urls_from_file(&connection.relays_path())?;
+                        format!("List of relays: {res:#?}")
+                    }
+                    RelaysCommands::Add { urls } => {
+                        let res = add_urls_to_file(&connection.relays_path(), urls)?;
+                        format!("Added relays: {res:#?}")
+                    }
+                    RelaysCommands::Remove { urls } => {
+                        let res = remove_urls_from_file(&connection.relays_path(), &urls)?;
+                        format!("Removed relays: {res:#?}")
+                    }
+                    RelaysCommands::Status => {
+                        let relay_processor = connection.connect().await?;
+                        let res = relay_processor.get_relays_status().await;
+                        format!("Relays status: {res:#?}")
+                    }
+                    RelaysCommands::Info => {
+                        let relay_processor = connection.connect().await?;
+                        let res = relay_processor.get_relays_info().await;
+                        format!("Relays information: {res:#?}")
+                    }
+                    RelaysCommands::Discover { pubkey, save } => {
+                        let relay_processor = connection.connect().await?;
+                        let res = relay_processor.get_relay_list(pubkey, None).await?;
+                        if save {
+                            let added = add_urls_to_file(&connection.relays_path(), res.clone())?;
+                            tracing::info!("Added relays to the file: {added:?}");
+                        }
+                        format!("Relays of '{pubkey}': {res:#?}")
                     }
                 },
-                Command::GetOrderReplies { event_id } => {
-                    let res = relay_processor.get_order_replies(event_id).await?;
+                Command::GetOrderReplies { event_id, query } => {
+                    let relay_processor = connection.connect().await?;
+                    let res = relay_processor
+                        .get_order_replies(event_id, &query.into_options())
+                        .await?;
                     format!("Order '{event_id}' replies: {res:#?}")
                 }
-                Command::ListOrders => {
-                    let res = relay_processor.list_orders().await?;
+                Command::ListOrders {
+                    discover_relays_of,
+                    min_pow,
+                    registered_only,
+                    reputation,
+                    wot,
+                    trusted_compilers_only,
+                    options_only,
+                    option_type,
+                    option_style,
+                    min_strike,
+                    max_strike,
+                    settles_before,
+                    collateral_asset,
+                    settlement_asset,
+                    query,
+                } => {
+                    let relay_processor = connection.connect().await?;
+                    let relay_processor = connection.with_maker_registry(relay_processor)?;
+                    let relay_processor = connection.with_compiler_allow_list(relay_processor)?;
+                    let option_filter = OptionTermsFilter {
+                        option_type,
+                        style: option_style,
+                        min_strike_price: min_strike,
+                        max_strike_price: max_strike,
+                        settles_before,
+                        collateral_asset,
+                        settlement_asset,
+                    };
+                    if let Some(maker_pubkey) = discover_relays_of {
+                        let added = relay_processor
+                            .use_relay_list(maker_pubkey, RelayMetadata::Write)
+                            .await?;
+                        tracing::info!("Added maker relays: {added:?}");
+                    }
+                    let res = relay_processor
+                        .list_annotated_orders(
+                            OrderFilter {
+                                min_pow_difficulty: min_pow,
+                                registered_only,
+                                with_reputation: reputation,
+                                web_of_trust_root: wot,
+                                trusted_compilers_only,
+                                option_terms: (options_only || option_filter.has_criteria()).then_some(option_filter),
+                            },
+                            &query.into_options(),
+                        )
+                        .await?;
                     format!("List of available orders: {res:#?}")
                 }
-                Command::GetEventsById { event_id } => {
-                    let res = relay_processor.get_events_by_id(event_id).await?;
+                Command::GetEventsById { event_id, query } => {
+                    let relay_processor = connection.connect().await?;
+                    let res = relay_processor
+                        .get_events_by_id(event_id, &query.into_options())
+                        .await?;
                     format!("List of available events: {res:#?}")
                 }
+                Command::Inspect {
+                    event_id: Some(event_id),
+                    ..
+                } => {
+                    let relay_processor = connection.connect().await?;
+                    relay_processor.inspect_event(event_id).await?.to_string()
+                }
+                Command::Inspect { event_id: None, file } => {
+                    let json = match file {
+                        Some(path) if path.as_os_str() != "-" => std::fs::read_to_string(path)?,
+                        _ => std::io::read_to_string(std::io::stdin())?,
+                    };
+                    let event = Event::from_json(json.trim()).map_err(CliError::InvalidEventJson)?;
+                    inspection::inspect_event(&event, Timestamp::now()).to_string()
+                }
+                Command::Export {
+                    output,
+                    author,
+                    since,
+                    until,
+                } => {
+                    let relay_processor = connection.connect().await?;
+                    let events = relay_processor
+                        .export_events(&ArchiveQuery {
+                            authors: (!author.is_empty()).then(|| author.into_iter().collect()),
+                            since: since.map(Timestamp::from),
+                            until: until.map(Timestamp::from),
+                        })
+                        .await?;
+                    let file = std::fs::File::create(&output)?;
+                    let res = archive::write_ndjson(&events, std::io::BufWriter::new(file))?;
+                    format!("Exported {res} events into {}", output.display())
+                }
+                Command::Import { input } => {
+                    let relay_processor = connection.connect().await?;
+                    let file = std::fs::File::open(input)?;
+                    let events = archive::read_ndjson(std::io::BufReader::new(file))?;
+                    let res = relay_processor.import_events(events).await?;
+                    format!("Importing events result: {res:#?}")
+                }
+                Command::PublishEvent { input } => {
+                    let relay_processor = connection.connect().await?;
+                    let event = offline::parse_signed_event(&std::fs::read_to_string(input)?)?;
+                    let res = relay_processor.publish_signed_event(&event).await?;
+                    format!("Publishing signed event result: {res:#?}")
+                }
+                Command::SignEvent { input, output } => {
+                    let keys = get_valid_key_from_file(&connection.key_path())?;
+                    let unsigned = offline::parse_unsigned_event(&std::fs::read_to_string(input)?)?;
+                    let event = offline::sign_unsigned_event(unsigned, keys.expose_secret())?;
+                    std::fs::write(&output, event.as_json())?;
+                    format!("Signed event '{}' written into {}", event.id, output.display())
+                }
             }
         };
         write_into_stdout(msg)?;
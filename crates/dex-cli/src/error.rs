@@ -1,6 +1,7 @@
 use crate::utils::FileError;
 
 use dex_nostr_relay::error::NostrRelayError;
+use global_utils::logger::LoggerError;
 
 pub type Result<T> = core::result::Result<T, CliError>;
 
@@ -12,4 +13,8 @@ pub enum CliError {
     File(#[from] FileError),
     #[error(transparent)]
     NostrRelay(#[from] NostrRelayError),
+    #[error("Invalid event JSON, err: {0}")]
+    InvalidEventJson(nostr::event::Error),
+    #[error(transparent)]
+    Logger(#[from] LoggerError),
 }
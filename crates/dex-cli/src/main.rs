@@ -1,15 +1,16 @@
 use clap::Parser;
 
-use global_utils::logger::init_logger;
+use global_utils::logger::init_logger_with_config;
 
 use simplicity_dex::cli::Cli;
 
 #[tokio::main]
 #[tracing::instrument]
 async fn main() -> anyhow::Result<()> {
-    let _logger_guard = init_logger();
+    let cli = Cli::parse();
+    let _logger_guard = init_logger_with_config(&cli.logger_config()?)?;
 
-    Cli::parse().process().await?;
+    cli.process().await?;
 
     Ok(())
 }
@@ -1,3 +1,4 @@
+use global_utils::secret::Secret;
 use nostr::{Keys, RelayUrl};
 use std::collections::HashSet;
 use std::io::BufRead;
@@ -6,6 +7,10 @@ use std::{io::Write, path::PathBuf};
 
 const DEFAULT_RELAYS_FILEPATH: &str = ".simplicity-dex/relays.txt";
 const DEFAULT_KEY_PATH: &str = ".simplicity-dex/keypair.txt";
+const DEFAULT_REGISTRY_PATH: &str = ".simplicity-dex/makers.txt";
+const DEFAULT_COMPILERS_PATH: &str = ".simplicity-dex/compilers.toml";
+const DEFAULT_ORACLES_PATH: &str = ".simplicity-dex/oracles.txt";
+const DEFAULT_LOGGER_CONFIG_PATH: &str = ".simplicity-dex/logger.toml";
 pub const DEFAULT_CLIENT_TIMEOUT_SECS: u64 = 10;
 
 pub fn write_into_stdout<T: AsRef<str> + std::fmt::Debug>(text: T) -> std::io::Result<usize> {
@@ -25,6 +30,30 @@ pub fn default_relays_path() -> PathBuf {
         .unwrap_or_else(|| PathBuf::from("../../.."))
         .join(DEFAULT_RELAYS_FILEPATH)
 }
+
+pub fn default_registry_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("../../.."))
+        .join(DEFAULT_REGISTRY_PATH)
+}
+
+pub fn default_compilers_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("../../.."))
+        .join(DEFAULT_COMPILERS_PATH)
+}
+
+pub fn default_oracles_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("../../.."))
+        .join(DEFAULT_ORACLES_PATH)
+}
+
+pub fn default_logger_config_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("../../.."))
+        .join(DEFAULT_LOGGER_CONFIG_PATH)
+}
 #[derive(Debug, thiserror::Error)]
 pub enum FileError {
     #[error("Unable to parse url: {1}, error: {0}")]
@@ -35,8 +64,8 @@ pub enum FileError {
     IncorrectPathToFile(PathBuf),
     #[error("File is empty, got path: {0}")]
     EmptyFile(PathBuf),
-    #[error("File is empty, got path: {0}")]
-    KeyParseError(nostr::key::Error, String),
+    #[error("Unable to parse key, error: {0}")]
+    KeyParseError(nostr::key::Error, Secret<String>),
 }
 
 pub fn check_file_existence(path: &str) -> Result<PathBuf, String> {
@@ -49,32 +78,110 @@ pub fn check_file_existence(path: &str) -> Result<PathBuf, String> {
     }
 }
 
-pub fn get_valid_urls_from_file(filepath: &PathBuf) -> Result<Vec<RelayUrl>, FileError> {
+/// Parses relay url from the line of relays file, blank lines and `#` comments are ignored.
+fn parse_relay_line(line: &str) -> Option<Result<RelayUrl, nostr::types::url::Error>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    Some(RelayUrl::parse(line))
+}
+
+fn read_lines(filepath: &PathBuf) -> Result<Vec<String>, FileError> {
     let file = std::fs::File::open(filepath).map_err(|x| FileError::ProblemWithFile(x, filepath.clone()))?;
     let reader = std::io::BufReader::new(file);
-    let mut set = HashSet::new();
-    for x in reader.lines() {
-        let line = x.map_err(|x| FileError::ProblemWithFile(x, filepath.clone()))?;
-        match RelayUrl::parse(&line) {
-            Ok(url) => {
-                set.insert(url);
-            }
-            Err(e) => {
-                return Err(FileError::UrlParseError(e, line));
+
+    reader
+        .lines()
+        .collect::<std::io::Result<Vec<String>>>()
+        .map_err(|x| FileError::ProblemWithFile(x, filepath.clone()))
+}
+
+fn write_lines(filepath: &PathBuf, lines: &[String]) -> Result<(), FileError> {
+    if let Some(parent) = filepath.parent() {
+        std::fs::create_dir_all(parent).map_err(|x| FileError::ProblemWithFile(x, filepath.clone()))?;
+    }
+
+    let mut content = lines.join("\n");
+    content.push('\n');
+    std::fs::write(filepath, content).map_err(|x| FileError::ProblemWithFile(x, filepath.clone()))
+}
+
+/// Reads relays from file, skipping malformed lines with a warning instead of failing.
+pub fn get_valid_urls_from_file(filepath: &PathBuf) -> Result<Vec<RelayUrl>, FileError> {
+    let mut urls = Vec::new();
+    for (line_number, line) in read_lines(filepath)?.iter().enumerate() {
+        match parse_relay_line(line) {
+            Some(Ok(url)) if !urls.contains(&url) => urls.push(url),
+            None | Some(Ok(_)) => {}
+            Some(Err(e)) => {
+                tracing::warn!(
+                    "{}",
+                    FileError::UrlParseError(e, format!("{line} ({}:{})", filepath.display(), line_number + 1))
+                );
             }
         }
     }
-    Ok(set.into_iter().collect::<Vec<RelayUrl>>())
+    Ok(urls)
+}
+
+/// Appends relays to the file (creating it if needed), returns relays which were not listed before.
+pub fn add_urls_to_file(filepath: &PathBuf, urls: Vec<RelayUrl>) -> Result<Vec<RelayUrl>, FileError> {
+    let mut lines = if filepath.is_file() {
+        read_lines(filepath)?
+    } else {
+        Vec::new()
+    };
+    let mut listed = lines
+        .iter()
+        .filter_map(|line| parse_relay_line(line)?.ok())
+        .collect::<HashSet<RelayUrl>>();
+
+    let mut added = Vec::new();
+    for url in urls {
+        if listed.insert(url.clone()) {
+            lines.push(url.to_string());
+            added.push(url);
+        }
+    }
+
+    if !added.is_empty() {
+        write_lines(filepath, &lines)?;
+    }
+    Ok(added)
+}
+
+/// Removes relays from the file keeping comments and other lines untouched, returns removed relays.
+pub fn remove_urls_from_file(filepath: &PathBuf, urls: &[RelayUrl]) -> Result<Vec<RelayUrl>, FileError> {
+    let mut removed = Vec::new();
+    let lines = read_lines(filepath)?
+        .into_iter()
+        .filter(|line| match parse_relay_line(line) {
+            Some(Ok(url)) if urls.contains(&url) => {
+                removed.push(url);
+                false
+            }
+            _ => true,
+        })
+        .collect::<Vec<String>>();
+
+    if !removed.is_empty() {
+        write_lines(filepath, &lines)?;
+    }
+    Ok(removed)
 }
 
-pub fn get_valid_key_from_file(filepath: &PathBuf) -> Result<Keys, FileError> {
+/// Keys stay wrapped in [`Secret`] until they are handed over to a signer.
+pub fn get_valid_key_from_file(filepath: &PathBuf) -> Result<Secret<Keys>, FileError> {
     let file = std::fs::File::open(filepath).map_err(|x| FileError::ProblemWithFile(x, filepath.clone()))?;
     let reader = std::io::BufReader::new(file);
     let key = reader
         .lines()
         .next()
         .ok_or_else(|| FileError::EmptyFile(filepath.clone()))?
+        .map(Secret::new)
         .map_err(|x| FileError::ProblemWithFile(x, filepath.clone()))?;
-    let key = Keys::from_str(&key).map_err(|e| FileError::KeyParseError(e, key))?;
-    Ok(key)
+    let keys = Keys::from_str(key.expose_secret()).map_err(|e| FileError::KeyParseError(e, key))?;
+    Ok(Secret::new(keys))
 }
@@ -0,0 +1,119 @@
+use crate::error::NostrRelayError;
+use crate::types::{
+    CustomKind, MakerAcceptKind, MakerOrderKind, OracleAttestationKind, QuoteKind, QuoteRequestKind, SettlementKind,
+    TakerOrderKind,
+};
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::io::{BufRead, Write};
+
+use nostr::{Event, EventId, Filter, JsonUtil, Kind, PublicKey, Timestamp};
+
+/// DEX events to archive, deletions are fetched separately and archived only if they reference
+/// archived events.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ArchiveQuery {
+    pub authors: Option<BTreeSet<PublicKey>>,
+    pub since: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+}
+
+/// Outcome of re-publishing an archive.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ImportReport {
+    pub published: Vec<EventId>,
+    /// Events which were not published together with the reason
+    pub skipped: Vec<(EventId, String)>,
+}
+
+/// Kinds of events produced by the DEX, NIP-09 deletions excluded.
+pub fn dex_kinds() -> BTreeSet<Kind> {
+    BTreeSet::from([
+        MakerOrderKind::get_kind(),
+        TakerOrderKind::get_kind(),
+        MakerAcceptKind::get_kind(),
+        OracleAttestationKind::get_kind(),
+        SettlementKind::get_kind(),
+        QuoteRequestKind::get_kind(),
+        QuoteKind::get_kind(),
+    ])
+}
+
+impl ArchiveQuery {
+    pub fn to_filter(&self) -> Filter {
+        Filter {
+            ids: None,
+            authors: self.authors.clone(),
+            kinds: Some(dex_kinds()),
+            search: None,
+            since: self.since,
+            until: self.until,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        }
+    }
+}
+
+/// Sorts events chronologically and drops deletions unrelated to the archived events.
+///
+/// Deletion is related if it references an archived event of its own author, as only the author
+/// may delete an event (NIP-09).
+pub fn prepare_archive(events: impl IntoIterator<Item = Event>) -> Vec<Event> {
+    let (deletions, mut events): (Vec<Event>, Vec<Event>) =
+        events.into_iter().partition(|x| x.kind == Kind::EventDeletion);
+
+    let archived = events
+        .iter()
+        .map(|x| (x.id, x.pubkey))
+        .collect::<HashSet<(EventId, PublicKey)>>();
+    events.extend(
+        deletions
+            .into_iter()
+            .filter(|x| x.tags.event_ids().any(|id| archived.contains(&(*id, x.pubkey)))),
+    );
+    events.sort_by_key(|x| (x.created_at, x.id));
+
+    events
+}
+
+/// Writes events as NDJSON, one event per line, returns the amount of written events.
+pub fn write_ndjson<'a>(events: impl IntoIterator<Item = &'a Event>, mut writer: impl Write) -> std::io::Result<usize> {
+    let mut written = 0;
+    for event in events {
+        writeln!(writer, "{}", event.as_json())?;
+        written += 1;
+    }
+    writer.flush()?;
+
+    Ok(written)
+}
+
+/// Reads NDJSON archive, every event must be a DEX event with a valid signature.
+///
+/// Blank lines are ignored, the first invalid line fails the whole archive.
+pub fn read_ndjson(reader: impl BufRead) -> crate::error::Result<Vec<Event>> {
+    let kinds = dex_kinds();
+    let mut events = Vec::new();
+    for (index, line) in reader.lines().enumerate() {
+        let to_error = |err: Box<dyn std::error::Error + Send + Sync>| NostrRelayError::InvalidArchive {
+            line: index + 1,
+            source: err,
+        };
+
+        let line = line.map_err(|err| to_error(err.into()))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let event = Event::from_json(&line).map_err(|err| to_error(err.into()))?;
+        event.verify().map_err(|err| to_error(err.into()))?;
+        if !kinds.contains(&event.kind) && event.kind != Kind::EventDeletion {
+            return Err(to_error(
+                format!("kind {} is not a DEX event", event.kind.as_u16()).into(),
+            ));
+        }
+        events.push(event);
+    }
+
+    Ok(events)
+}
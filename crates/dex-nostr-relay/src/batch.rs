@@ -0,0 +1,158 @@
+use crate::compiler_provenance::validate_compiler_tag;
+use crate::error::NostrRelayError;
+use crate::relay_processor::OrderPlaceEventTags;
+
+use std::fmt;
+use std::str::FromStr;
+
+use nostr::EventId;
+use serde::{Deserialize, Serialize};
+
+/// How order amounts change along the ladder, from the lowest to the highest price.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeDistribution {
+    #[default]
+    Uniform,
+    Increasing,
+    Decreasing,
+}
+
+/// Ladder of orders evenly spread over the price range, sharing `total_amount` of `asset_to_sell`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LadderSpec {
+    pub asset_to_sell: String,
+    pub asset_to_buy: String,
+    pub min_price: u64,
+    pub max_price: u64,
+    pub orders: usize,
+    pub total_amount: u64,
+    #[serde(default)]
+    pub distribution: SizeDistribution,
+    pub compiler_name: String,
+    pub compiler_build_hash: String,
+}
+
+/// Order of the batch which was not published.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedPlacement {
+    /// Position of the order in the batch
+    pub index: usize,
+    pub err_msg: String,
+}
+
+/// Combined outcome of the batch placement.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct BatchPlacementReport {
+    /// Published orders together with their position in the batch
+    pub placed: Vec<(usize, EventId)>,
+    pub failed: Vec<FailedPlacement>,
+    /// Deletion request (NIP-09) withdrawing placed orders after a failed all-or-nothing batch,
+    /// or the error of publishing it, in which case placed orders are still live
+    pub cancellation: Option<Result<EventId, String>>,
+}
+
+fn ladder_error(err_msg: impl Into<String>) -> NostrRelayError {
+    NostrRelayError::InvalidLadderSpec {
+        err_msg: err_msg.into(),
+    }
+}
+
+impl fmt::Display for SizeDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SizeDistribution::Uniform => write!(f, "uniform"),
+            SizeDistribution::Increasing => write!(f, "increasing"),
+            SizeDistribution::Decreasing => write!(f, "decreasing"),
+        }
+    }
+}
+
+impl FromStr for SizeDistribution {
+    type Err = NostrRelayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "uniform" => Ok(SizeDistribution::Uniform),
+            "increasing" => Ok(SizeDistribution::Increasing),
+            "decreasing" => Ok(SizeDistribution::Decreasing),
+            _ => Err(ladder_error(format!("unknown size distribution '{s}'"))),
+        }
+    }
+}
+
+impl SizeDistribution {
+    /// Relative weight of the order at `index`, weights grow linearly for non-uniform distributions.
+    fn weight(self, index: usize, orders: usize) -> u64 {
+        match self {
+            SizeDistribution::Uniform => 1,
+            SizeDistribution::Increasing => index as u64 + 1,
+            SizeDistribution::Decreasing => (orders - index) as u64,
+        }
+    }
+}
+
+impl LadderSpec {
+    /// Generates orders sorted by price, the rounding remainder of amounts goes to the last order.
+    pub fn generate(&self) -> crate::error::Result<Vec<OrderPlaceEventTags>> {
+        if self.orders == 0 {
+            return Err(ladder_error("ladder must contain at least one order"));
+        }
+        if self.min_price == 0 || self.min_price > self.max_price {
+            return Err(ladder_error(format!(
+                "invalid price range {}..={}",
+                self.min_price, self.max_price
+            )));
+        }
+        if self.orders > 1 && self.min_price == self.max_price {
+            return Err(ladder_error("price range must not be empty for several orders"));
+        }
+        // Placement requires the compiler tag, so a ladder is not published partially because of it
+        validate_compiler_tag(&self.compiler_name, &self.compiler_build_hash)
+            .map_err(|err| ladder_error(err.to_string()))?;
+
+        let weights = (0..self.orders)
+            .map(|index| self.distribution.weight(index, self.orders))
+            .collect::<Vec<u64>>();
+        let total_weight = u128::from(weights.iter().sum::<u64>());
+        let mut amounts = weights
+            .iter()
+            .map(|weight| (u128::from(self.total_amount) * u128::from(*weight) / total_weight) as u64)
+            .collect::<Vec<u64>>();
+        let remainder = self.total_amount - amounts.iter().sum::<u64>();
+        *amounts.last_mut().expect("ladder is not empty") += remainder;
+        if amounts.contains(&0) {
+            return Err(ladder_error(format!(
+                "total amount {} is too small for {} orders",
+                self.total_amount, self.orders
+            )));
+        }
+
+        let step = match self.orders {
+            1 => 0.0,
+            orders => (self.max_price - self.min_price) as f64 / (orders - 1) as f64,
+        };
+        let orders = amounts
+            .into_iter()
+            .enumerate()
+            .map(|(index, amount)| OrderPlaceEventTags {
+                asset_to_sell: self.asset_to_sell.clone(),
+                asset_to_buy: self.asset_to_buy.clone(),
+                price: self.min_price + (step * index as f64).round() as u64,
+                amount: Some(amount),
+                compiler_name: self.compiler_name.clone(),
+                compiler_build_hash: self.compiler_build_hash.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        Ok(orders)
+    }
+}
+
+impl BatchPlacementReport {
+    /// Returns `true` if every order of the batch is published.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
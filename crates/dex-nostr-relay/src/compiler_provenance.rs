@@ -0,0 +1,148 @@
+use crate::error::{NostrRelayError, SourceError};
+
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Trust level of the toolchain which compiled order contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompilerStatus {
+    Trusted,
+    Unknown,
+    Blacklisted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct TrustedCompiler {
+    pub name: String,
+    pub build_hash: String,
+}
+
+/// Blacklist entry, missing `build_hash` blacklists every build of the compiler version.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BlacklistedCompiler {
+    pub name: String,
+    pub build_hash: Option<String>,
+}
+
+/// Allow-list of Simplicity compiler versions and their deterministic build hashes.
+///
+/// Entries with an empty name or a build hash which is not hex are skipped with a warning.
+///
+/// Loaded from TOML:
+/// ```toml
+/// [[trusted]]
+/// name = "simplicity-v1.2.3"
+/// build_hash = "<deterministic_build_hash>"
+///
+/// [[blacklisted]]
+/// name = "simplicity-v1.0.0"
+/// ```
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct CompilerAllowList {
+    #[serde(default)]
+    pub trusted: Vec<TrustedCompiler>,
+    #[serde(default)]
+    pub blacklisted: Vec<BlacklistedCompiler>,
+}
+
+impl CompilerAllowList {
+    pub fn from_toml_str(config: &str) -> crate::error::Result<Self> {
+        toml::from_str::<Self>(config)
+            .map(|x| x.without_invalid_entries("toml"))
+            .map_err(|err| NostrRelayError::FailedToLoadCompilerAllowList {
+                source_name: "toml".to_string(),
+                source: err.into(),
+            })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let to_error = |source: SourceError| NostrRelayError::FailedToLoadCompilerAllowList {
+            source_name: path.display().to_string(),
+            source,
+        };
+
+        let config = std::fs::read_to_string(path).map_err(|err| to_error(err.into()))?;
+        toml::from_str::<Self>(&config)
+            .map(|x| x.without_invalid_entries(&path.display().to_string()))
+            .map_err(|err| to_error(err.into()))
+    }
+
+    fn without_invalid_entries(mut self, source_name: &str) -> Self {
+        self.trusted
+            .retain(|x| match validate_compiler_tag(&x.name, &x.build_hash) {
+                Ok(()) => true,
+                Err(err) => {
+                    tracing::warn!("Skipping trusted compiler '{}' ({source_name}), {err}", x.name);
+                    false
+                }
+            });
+        self.blacklisted.retain(|x| {
+            let res = validate_compiler_name(&x.name)
+                .and_then(|()| x.build_hash.as_deref().map_or(Ok(()), validate_build_hash));
+            match res {
+                Ok(()) => true,
+                Err(err) => {
+                    tracing::warn!("Skipping blacklisted compiler '{}' ({source_name}), {err}", x.name);
+                    false
+                }
+            }
+        });
+        self
+    }
+
+    /// Blacklist takes precedence over trusted entries.
+    pub fn status(&self, name: &str, build_hash: &str) -> CompilerStatus {
+        let blacklisted = self.blacklisted.iter().any(|x| {
+            x.name == name
+                && x.build_hash
+                    .as_deref()
+                    .is_none_or(|hash| hash.eq_ignore_ascii_case(build_hash))
+        });
+        if blacklisted {
+            return CompilerStatus::Blacklisted;
+        }
+
+        let trusted = self
+            .trusted
+            .iter()
+            .any(|x| x.name == name && x.build_hash.eq_ignore_ascii_case(build_hash));
+        if trusted {
+            CompilerStatus::Trusted
+        } else {
+            CompilerStatus::Unknown
+        }
+    }
+}
+
+/// Checks that `compiler` tag values look like a compiler version and a hex encoded build hash.
+pub fn validate_compiler_tag(name: &str, build_hash: &str) -> crate::error::Result<()> {
+    validate_compiler_name(name)?;
+    validate_build_hash(build_hash)
+}
+
+fn validate_compiler_name(name: &str) -> crate::error::Result<()> {
+    if name.trim().is_empty() {
+        return Err(NostrRelayError::InvalidOrderTag {
+            tag: "compiler".to_string(),
+            err_msg: "compiler name is empty".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+fn validate_build_hash(build_hash: &str) -> crate::error::Result<()> {
+    if build_hash.is_empty()
+        || !build_hash.len().is_multiple_of(2)
+        || !build_hash.chars().all(|x| x.is_ascii_hexdigit())
+    {
+        return Err(NostrRelayError::InvalidOrderTag {
+            tag: "compiler".to_string(),
+            err_msg: format!("build hash '{build_hash}' is not a hex string"),
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,173 @@
+use crate::error::NostrRelayError;
+use crate::relay_processor::OrderPlaceEventTags;
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use simplicity::dag::{DagLike, MaxSharing};
+use simplicity::elements::secp256k1_zkp::{Secp256k1, XOnlyPublicKey};
+use simplicity::elements::taproot::TaprootBuilder;
+use simplicity::elements::{Address, Script};
+use simplicity::jet::Elements;
+use simplicity::node::{Commit, Inner};
+use simplicity::{Cmr, CommitNode};
+
+/// Unspendable x-only internal key (NUMS point) used for Simplicity contract addresses.
+pub const UNSPENDABLE_INTERNAL_KEY: &str = "50929b74c1a04954b78b4b6035e97a5e078a5a0f28ec96d547bfee9ace803ac0";
+
+/// Result of decoding and verifying order contract against the values advertised in order tags.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractSummary {
+    pub cmr: Cmr,
+    pub source_type: String,
+    pub target_type: String,
+    /// Amount of unique nodes in the program
+    pub node_count: usize,
+    pub jets: BTreeSet<String>,
+    pub advertised_cmr: Option<String>,
+    pub advertised_address: Option<String>,
+    /// `None` if the order does not advertise CMR
+    pub cmr_matches: Option<bool>,
+    /// `None` if the order does not advertise contract address
+    pub address_matches: Option<bool>,
+}
+
+impl ContractSummary {
+    /// Program is verified if it commits to the advertised contract address and the advertised CMR,
+    /// if any, matches as well.
+    ///
+    /// Matching CMR alone is not enough: the funds are locked by the address, not by the CMR tag.
+    pub fn is_verified(&self) -> bool {
+        self.address_matches == Some(true) && self.cmr_matches != Some(false)
+    }
+}
+
+impl fmt::Display for ContractSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let check = |matches: Option<bool>| match matches {
+            Some(true) => "match",
+            Some(false) => "MISMATCH",
+            None => "not advertised",
+        };
+
+        writeln!(f, "Program type: {} → {}", self.source_type, self.target_type)?;
+        writeln!(f, "Program nodes: {}", self.node_count)?;
+        if self.jets.is_empty() {
+            writeln!(f, "Jets: none")?;
+        } else {
+            writeln!(f, "Jets: {}", self.jets.iter().cloned().collect::<Vec<_>>().join(", "))?;
+        }
+        writeln!(f, "Computed CMR: {}", self.cmr)?;
+        writeln!(
+            f,
+            "Advertised CMR: {} [{}]",
+            self.advertised_cmr.as_deref().unwrap_or("-"),
+            check(self.cmr_matches)
+        )?;
+        writeln!(
+            f,
+            "Advertised address: {} [{}]",
+            self.advertised_address.as_deref().unwrap_or("-"),
+            check(self.address_matches)
+        )?;
+        write!(
+            f,
+            "Verification: {}",
+            if self.is_verified() { "PASSED" } else { "FAILED" }
+        )
+    }
+}
+
+/// Decodes base64 encoded Simplicity program without witness data.
+///
+/// Types are inferred during decoding, so returned program is type-checked; additionally it must
+/// have type `1 → 1` to be usable as a contract.
+pub fn decode_program(program: &str) -> crate::error::Result<Arc<CommitNode<Elements>>> {
+    let program = CommitNode::<Elements>::from_str(program.trim())
+        .map_err(|err| NostrRelayError::InvalidContractProgram { source: Box::new(err) })?;
+
+    let arrow = program.arrow();
+    if !arrow.source.is_unit() || !arrow.target.is_unit() {
+        return Err(NostrRelayError::InvalidContractProgram {
+            source: format!("program has type {arrow}, expected 1 → 1").into(),
+        });
+    }
+
+    Ok(program)
+}
+
+/// Computes script pubkey of the taproot output committing to the single Simplicity leaf.
+pub fn contract_script_pubkey(cmr: Cmr) -> Script {
+    let secp = Secp256k1::verification_only();
+    let internal_key = XOnlyPublicKey::from_str(UNSPENDABLE_INTERNAL_KEY).expect("constant internal key");
+    let spend_info = TaprootBuilder::new()
+        .add_leaf_with_ver(
+            0,
+            Script::from(cmr.to_byte_array().to_vec()),
+            simplicity::leaf_version(),
+        )
+        .expect("single leaf at depth 0")
+        .finalize(&secp, internal_key)
+        .expect("tap tree with a single leaf is complete");
+
+    Script::new_v1_p2tr_tweaked(spend_info.output_key())
+}
+
+/// Decodes the program and compares its CMR and taproot address with the advertised ones.
+pub fn verify_program(
+    program: &str,
+    advertised_cmr: Option<&str>,
+    advertised_address: Option<&str>,
+) -> crate::error::Result<ContractSummary> {
+    let program = decode_program(program)?;
+    let cmr = program.cmr();
+
+    let mut node_count = 0;
+    let mut jets = BTreeSet::new();
+    for data in program.as_ref().post_order_iter::<MaxSharing<Commit<Elements>>>() {
+        node_count += 1;
+        if let Inner::Jet(jet) = data.node.inner() {
+            jets.insert(jet.to_string());
+        }
+    }
+
+    let cmr_matches = advertised_cmr.map(|x| x.trim().eq_ignore_ascii_case(&cmr.to_string()));
+    let address_matches = match advertised_address {
+        None => None,
+        Some(address) => {
+            let address = Address::from_str(address.trim()).map_err(|err| NostrRelayError::InvalidOrderTag {
+                tag: "contract_address".to_string(),
+                err_msg: format!("'{address}' is not a valid address, {err}"),
+            })?;
+            Some(address.script_pubkey() == contract_script_pubkey(cmr))
+        }
+    };
+
+    let arrow = program.arrow();
+    Ok(ContractSummary {
+        cmr,
+        source_type: arrow.source.to_string(),
+        target_type: arrow.target.to_string(),
+        node_count,
+        jets,
+        advertised_cmr: advertised_cmr.map(ToString::to_string),
+        advertised_address: advertised_address.map(ToString::to_string),
+        cmr_matches,
+        address_matches,
+    })
+}
+
+/// Verifies contract carried by the maker order, see [`verify_program`].
+pub fn verify_order(tags: &OrderPlaceEventTags) -> crate::error::Result<ContractSummary> {
+    let program = tags
+        .contract_program
+        .as_deref()
+        .ok_or_else(|| NostrRelayError::InvalidOrderTag {
+            tag: "contract".to_string(),
+            err_msg: "order does not carry contract program".to_string(),
+        })?;
+
+    verify_program(program, tags.contract_cmr.as_deref(), tags.contract_address.as_deref())
+}
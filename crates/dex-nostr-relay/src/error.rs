@@ -1,5 +1,18 @@
-use nostr::SignerError;
+use crate::relay_health::RelayOperation;
+
+use std::fmt;
+use std::time::Duration;
+
 use nostr::filter::SingleLetterTagError;
+use nostr::{EventId, RelayUrl, SignerError};
+use nostr_sdk::pool::{pool, relay};
+
+/// Reason a single relay refused or failed the operation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayFailure {
+    pub url: RelayUrl,
+    pub reason: String,
+}
 
 #[derive(thiserror::Error, Debug)]
 pub enum NostrRelayError {
@@ -7,12 +20,211 @@ pub enum NostrRelayError {
     Signer(#[from] SignerError),
     #[error("Single letter error: {0}")]
     SingleLetterTag(#[from] SingleLetterTagError),
-    #[error("Failed to convert custom url to RelayURL, err: {err_msg}")]
-    FailedToConvertRelayUrl { err_msg: String },
+    #[error("Invalid relay url '{url}', err: {source}")]
+    InvalidRelayUrl {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Failed to add relay '{url}', err: {source}")]
+    FailedToAddRelay {
+        url: String,
+        #[source]
+        source: nostr_sdk::client::Error,
+    },
+    #[error("Failed to fetch relay information document from {url}, err: {source}")]
+    FailedToFetchRelayInformation {
+        url: RelayUrl,
+        #[source]
+        source: reqwest::Error,
+    },
     #[error("An error occurred in Nostr Client, err: {0}")]
-    NostrClientFailure(#[from] nostr_sdk::client::Error),
+    NostrClientFailure(nostr_sdk::client::Error),
+    #[error("Event not found, id: {0}")]
+    EventNotFound(EventId),
+    #[error("Event expired, id: {0}")]
+    ExpiredEvent(EventId),
+    #[error("Relay {operation} timed out after {timeout:?}")]
+    Timeout {
+        operation: RelayOperation,
+        timeout: Duration,
+    },
+    #[error("No relays connected for {operation}")]
+    NoRelaysConnected { operation: RelayOperation },
+    #[error("All relays rejected {operation}: {}", format_failures(.failures))]
+    AllRelaysRejected {
+        operation: RelayOperation,
+        failures: Vec<RelayFailure>,
+    },
     #[error("Relay Client requires for operation signature, add key to the Client")]
     MissingSigner,
+    #[error("Signer of the Relay Client is unavailable, err: {source}")]
+    SignerUnavailable {
+        #[source]
+        source: nostr_sdk::client::Error,
+    },
+    #[error("Failed to load maker registry from {source_name}, err: {source}")]
+    FailedToLoadMakerRegistry {
+        source_name: String,
+        #[source]
+        source: SourceError,
+    },
+    #[error("Maker registry is required for filtering registered makers, configure it for the processor")]
+    MissingMakerRegistry,
+    #[error("Failed to load compiler allow-list from {source_name}, err: {source}")]
+    FailedToLoadCompilerAllowList {
+        source_name: String,
+        #[source]
+        source: SourceError,
+    },
+    #[error("Compiler allow-list is required for filtering trusted compilers, configure it for the processor")]
+    MissingCompilerAllowList,
+    #[error("Failed to load oracle set from {source_name}, err: {source}")]
+    FailedToLoadOracleSet {
+        source_name: String,
+        #[source]
+        source: SourceError,
+    },
+    #[error("Oracle set is required for fetching price attestations, configure it for the processor")]
+    MissingOracleSet,
+    #[error("Invalid price attestation {event_id}, err: {source}")]
+    InvalidPriceAttestation {
+        event_id: EventId,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Not enough oracle attestations, required: {required}, available: {available}")]
+    NotEnoughAttestations { required: usize, available: usize },
+    #[error("Missing order tag '{tag}' value at position {index}")]
+    MissingOrderTag { tag: String, index: usize },
+    #[error("Invalid order tag '{tag}', err: {err_msg}")]
+    InvalidOrderTag { tag: String, err_msg: String },
+    #[error("Invalid Simplicity contract program, err: {source}")]
+    InvalidContractProgram {
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Invalid swap proposal, err: {err_msg}")]
+    InvalidSwapProposal { err_msg: String },
+    #[error("Failed to decode PSET, err: {0}")]
+    InvalidPsetEncoding(#[from] elements::pset::ParseError),
+    #[error("Invalid PSET, err: {0}")]
+    InvalidPset(#[from] elements::pset::Error),
+    #[error("Order {order_id} is not authored by the client key")]
+    NotOrderAuthor { order_id: EventId },
+    #[error("Invalid settlement, err: {err_msg}")]
+    InvalidSettlement { err_msg: String },
+    #[error("Failed to load keeper spec from {source_name}, err: {source}")]
+    FailedToLoadKeeperSpec {
+        source_name: String,
+        #[source]
+        source: SourceError,
+    },
+    #[error("Invalid order ladder, err: {err_msg}")]
+    InvalidLadderSpec { err_msg: String },
+    #[error("Invalid offline signed event, err: {source}")]
+    InvalidOfflineEvent {
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Invalid event archive at line {line}, err: {source}")]
+    InvalidArchive {
+        line: usize,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("Unexpected event kind, expected: {expected}, got: {got}")]
+    UnexpectedEventKind { expected: u16, got: u16 },
+    #[error("Failed to mine proof-of-work, err: {source}")]
+    PowMiningFailed {
+        #[source]
+        source: tokio::task::JoinError,
+    },
+    #[error("Not enough healthy relays, required: {required}, available: {available}")]
+    NotEnoughHealthyRelays { required: usize, available: usize },
+}
+
+/// Cause of failing to load a file or to query an endpoint backing a configured source.
+#[derive(thiserror::Error, Debug)]
+pub enum SourceError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Http(#[from] reqwest::Error),
+    #[error(transparent)]
+    Toml(#[from] toml::de::Error),
+    #[error("JSON-RPC error: {0}")]
+    Rpc(serde_json::Value),
+    #[error("unexpected response: {0}")]
+    UnexpectedResponse(serde_json::Value),
 }
 
 pub type Result<T> = std::result::Result<T, NostrRelayError>;
+
+fn format_failures(failures: &[RelayFailure]) -> String {
+    failures
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join("; ")
+}
+
+/// Returns `true` for client errors caused by relay connectivity rather than by the request itself.
+fn is_transient_client_error(err: &nostr_sdk::client::Error) -> bool {
+    match err {
+        nostr_sdk::client::Error::Relay(err) => is_transient_relay_error(err),
+        nostr_sdk::client::Error::RelayPool(pool::Error::Relay(err)) => is_transient_relay_error(err),
+        _ => false,
+    }
+}
+
+/// Returns `true` for relay errors caused by connectivity rather than by the request itself.
+fn is_transient_relay_error(err: &relay::Error) -> bool {
+    matches!(
+        err,
+        relay::Error::Transport(_)
+            | relay::Error::Timeout
+            | relay::Error::NotRepliedToPing
+            | relay::Error::CantSendChannelMessage { .. }
+            | relay::Error::NotReady
+            | relay::Error::NotConnected
+            | relay::Error::Sleeping
+    )
+}
+
+impl fmt::Display for RelayFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.url, self.reason)
+    }
+}
+
+impl RelayFailure {
+    /// Classifies the failure by NIP-01 machine-readable prefix, unprefixed reasons are treated as transient.
+    pub fn is_transient(&self) -> bool {
+        const PERMANENT_PREFIXES: [&str; 4] = ["invalid:", "blocked:", "pow:", "restricted:"];
+
+        !self.is_duplicate() && !PERMANENT_PREFIXES.iter().any(|prefix| self.reason.starts_with(prefix))
+    }
+
+    /// Relay already stores the event, e.g. accepted by an earlier attempt whose reply was lost.
+    pub fn is_duplicate(&self) -> bool {
+        self.reason.starts_with("duplicate:")
+    }
+}
+
+impl NostrRelayError {
+    /// Returns `true` if repeating the same operation later may succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            NostrRelayError::Timeout { .. }
+            | NostrRelayError::NoRelaysConnected { .. }
+            | NostrRelayError::NotEnoughHealthyRelays { .. }
+            | NostrRelayError::FailedToFetchRelayInformation { .. } => true,
+            NostrRelayError::AllRelaysRejected { failures, .. } => failures.iter().any(RelayFailure::is_transient),
+            NostrRelayError::NostrClientFailure(err) | NostrRelayError::FailedToAddRelay { source: err, .. } => {
+                is_transient_client_error(err)
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::error::NostrRelayError;
+use crate::pow;
+use crate::relay_client::RelayClient;
+use crate::types::{BLOCKSTREAM_MAKER_ACCEPT_CONTENT, CustomKind, MakerAcceptKind, MakerOrderKind, TakerOrderKind};
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::{EventBuilder, EventId, Filter, Tag, TagKind, Timestamp};
+
+pub async fn handle(client: &RelayClient, reply_event_id: EventId) -> crate::error::Result<EventId> {
+    let client_signer = client.get_signer().await?;
+    let client_pubkey = client_signer.get_public_key().await?;
+
+    let reply = client
+        .req_and_wait(Filter {
+            ids: Some(BTreeSet::from([reply_event_id])),
+            authors: None,
+            kinds: Some(BTreeSet::from([TakerOrderKind::get_kind()])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        })
+        .await?
+        .first_owned()
+        .ok_or(NostrRelayError::EventNotFound(reply_event_id))?;
+    let first_referenced_id = *reply
+        .tags
+        .event_ids()
+        .next()
+        .ok_or(NostrRelayError::EventNotFound(reply_event_id))?;
+    let referenced_ids = reply.tags.event_ids().copied().collect::<BTreeSet<EventId>>();
+
+    // A reply may reference several events, pick the order this maker authored
+    let orders = client
+        .req_and_wait(Filter {
+            ids: Some(referenced_ids),
+            authors: None,
+            kinds: Some(BTreeSet::from([MakerOrderKind::get_kind()])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        })
+        .await?;
+    if orders.is_empty() {
+        return Err(NostrRelayError::EventNotFound(first_referenced_id));
+    }
+    let order_event_id = orders
+        .into_iter()
+        .find(|x| x.pubkey == client_pubkey)
+        .map(|x| x.id)
+        .ok_or(NostrRelayError::NotOrderAuthor {
+            order_id: first_referenced_id,
+        })?;
+
+    let timestamp_now = Timestamp::now();
+
+    let maker_accept = EventBuilder::new(MakerAcceptKind::get_kind(), BLOCKSTREAM_MAKER_ACCEPT_CONTENT)
+        .tags([
+            Tag::public_key(reply.pubkey),
+            Tag::event(order_event_id),
+            Tag::custom(TagKind::Custom(Cow::from("reply_event_id")), [reply_event_id]),
+        ])
+        .custom_created_at(timestamp_now);
+
+    let accept_event = pow::build_event(maker_accept, client_pubkey, client.pow_difficulty()).await?;
+    let accept_event = client_signer.sign_event(accept_event).await?;
+
+    let event_id = client.publish_event(&accept_event).await?;
+
+    Ok(event_id)
+}
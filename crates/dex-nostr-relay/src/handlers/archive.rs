@@ -0,0 +1,56 @@
+use crate::archive::{ArchiveQuery, ImportReport, prepare_archive};
+use crate::relay_client::RelayClient;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::{Event, Filter, Kind, SingleLetterTag};
+
+/// Fetches DEX events of the query, then deletions referencing them in a second request.
+pub async fn export(client: &RelayClient, query: &ArchiveQuery) -> crate::error::Result<Vec<Event>> {
+    let events = client.req_and_wait(query.to_filter()).await?;
+    if events.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let exported_ids = events.iter().map(|x| x.id.to_string()).collect::<BTreeSet<String>>();
+    let deletions = client
+        .req_and_wait(Filter {
+            ids: None,
+            authors: None,
+            kinds: Some(BTreeSet::from([Kind::EventDeletion])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::from([(SingleLetterTag::from_char('e')?, exported_ids)]),
+        })
+        .await?;
+
+    Ok(prepare_archive(events.into_iter().chain(deletions)))
+}
+
+/// Re-publishes archived events in order, failures of single events are collected into the report.
+pub async fn import(client: &RelayClient, events: Vec<Event>) -> crate::error::Result<ImportReport> {
+    let mut report = ImportReport::default();
+    for event in events {
+        if event.is_expired() {
+            report.skipped.push((event.id, "event is expired".to_string()));
+            continue;
+        }
+
+        let res = if event.tags.expiration().is_some() {
+            client.publish_expiring_event(&event).await
+        } else {
+            client.publish_event(&event).await
+        };
+        match res {
+            Ok(event_id) => report.published.push(event_id),
+            Err(err) => {
+                tracing::warn!(event_id = %event.id, error = %err, "Failed to import event");
+                report.skipped.push((event.id, err.to_string()));
+            }
+        }
+    }
+
+    Ok(report)
+}
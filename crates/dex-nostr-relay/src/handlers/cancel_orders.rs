@@ -0,0 +1,22 @@
+use crate::pow;
+use crate::relay_client::RelayClient;
+
+use nostr::nips::nip09::EventDeletionRequest;
+use nostr::{EventBuilder, EventId};
+
+/// Withdraws own orders with a deletion request (NIP-09).
+pub async fn handle(
+    client: &RelayClient,
+    order_ids: impl IntoIterator<Item = EventId>,
+    reason: &str,
+) -> crate::error::Result<EventId> {
+    let client_signer = client.get_signer().await?;
+    let client_pubkey = client_signer.get_public_key().await?;
+
+    let deletion = EventBuilder::delete(EventDeletionRequest::new().ids(order_ids).reason(reason));
+
+    let deletion = pow::build_event(deletion, client_pubkey, client.pow_difficulty()).await?;
+    let deletion = client_signer.sign_event(deletion).await?;
+
+    client.publish_event(&deletion).await
+}
@@ -1,4 +1,5 @@
 pub mod ids {
+    use crate::query::{self, QueryOptions};
     use crate::relay_client::RelayClient;
 
     use std::collections::{BTreeMap, BTreeSet};
@@ -6,9 +7,14 @@ pub mod ids {
     use nostr::{EventId, Filter};
     use nostr_sdk::prelude::Events;
 
-    pub async fn handle(client: &RelayClient, event_id: EventId) -> crate::error::Result<Events> {
-        let events = client
-            .req_and_wait(Filter {
+    pub async fn handle(
+        client: &RelayClient,
+        event_id: EventId,
+        options: &QueryOptions,
+    ) -> crate::error::Result<Events> {
+        let events = query::query_events(
+            client,
+            Filter {
                 ids: Some(BTreeSet::from([event_id])),
                 authors: None,
                 kinds: None,
@@ -17,8 +23,10 @@ pub mod ids {
                 until: None,
                 limit: None,
                 generic_tags: BTreeMap::default(),
-            })
-            .await?;
+            },
+            options,
+        )
+        .await?;
         Ok(events)
     }
 }
@@ -0,0 +1,31 @@
+use crate::contract_verification;
+use crate::error::NostrRelayError;
+use crate::relay_client::RelayClient;
+use crate::relay_processor::{InspectedOrder, OrderPlaceEventTags};
+use crate::types::{CustomKind, MakerOrderKind};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::{EventId, Filter};
+
+pub async fn handle(client: &RelayClient, event_id: EventId) -> crate::error::Result<InspectedOrder> {
+    let event = client
+        .req_and_wait(Filter {
+            ids: Some(BTreeSet::from([event_id])),
+            authors: None,
+            kinds: Some(BTreeSet::from([MakerOrderKind::get_kind()])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        })
+        .await?
+        .first_owned()
+        .ok_or(NostrRelayError::EventNotFound(event_id))?;
+
+    let tags = OrderPlaceEventTags::try_from(&event)?;
+    let contract = contract_verification::verify_order(&tags)?;
+
+    Ok(InspectedOrder { event, tags, contract })
+}
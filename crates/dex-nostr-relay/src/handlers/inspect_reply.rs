@@ -0,0 +1,29 @@
+use crate::error::NostrRelayError;
+use crate::handlers::settle_order::load_thread;
+use crate::relay_client::RelayClient;
+use crate::swap::{self, SwapSummary};
+
+use std::str::FromStr;
+
+use elements::Script;
+use elements::pset::PartiallySignedTransaction;
+use nostr::EventId;
+
+/// Loads taker reply with its order and validates the proposed swap PSET from the maker perspective.
+pub async fn handle(
+    client: &RelayClient,
+    reply_event_id: EventId,
+    maker_script: &Script,
+) -> crate::error::Result<SwapSummary> {
+    let thread = load_thread(client, reply_event_id).await?;
+    let pset = thread
+        .reply_tags
+        .swap_pset
+        .as_deref()
+        .ok_or_else(|| NostrRelayError::InvalidSwapProposal {
+            err_msg: format!("reply {reply_event_id} does not carry swap PSET"),
+        })?;
+    let pset = PartiallySignedTransaction::from_str(pset)?;
+
+    swap::validate_swap_pset(&pset, &thread.order_tags, maker_script)
+}
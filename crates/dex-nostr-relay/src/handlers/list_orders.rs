@@ -1,38 +1,188 @@
 use crate::types::{CustomKind, MakerOrderKind};
 
+use crate::compiler_provenance::{CompilerAllowList, CompilerStatus};
+use crate::error::NostrRelayError;
+use crate::handlers::reputation;
+use crate::maker_registry::MakerRegistry;
+use crate::metrics;
+use crate::option_terms::{OptionTerms, OptionTermsFilter};
+use crate::pow;
+use crate::query::{self, QueryOptions};
 use crate::relay_client::RelayClient;
+use crate::relay_processor::{ListedOrder, OrderFilter, OrderPlaceEventTags};
 
-use std::collections::{BTreeMap, BTreeSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::pin::pin;
 
-use nostr::{Filter, Timestamp};
+use futures_util::future;
+use futures_util::stream::{Stream, StreamExt, TryStreamExt};
+
+use nostr::{Event, Filter, PublicKey};
 use nostr_sdk::prelude::Events;
 
-pub async fn handle(client: &RelayClient) -> crate::error::Result<Events> {
-    let events = client
-        .req_and_wait(Filter {
-            ids: None,
-            authors: None,
-            kinds: Some(BTreeSet::from([MakerOrderKind::get_kind()])),
-            search: None,
-            since: None,
-            until: None,
-            limit: None,
-            generic_tags: BTreeMap::default(),
+/// Streams listed orders newest first, walking back through relay history page by page.
+pub fn handle_stream(
+    client: &RelayClient,
+    order_filter: OrderFilter,
+    options: QueryOptions,
+) -> impl Stream<Item = crate::error::Result<Event>> + '_ {
+    let filter = Filter {
+        ids: None,
+        authors: None,
+        kinds: Some(BTreeSet::from([MakerOrderKind::get_kind()])),
+        search: None,
+        since: None,
+        until: None,
+        limit: None,
+        generic_tags: BTreeMap::default(),
+    };
+    query::query_stream(client, filter, &options)
+        .inspect_ok(|event| metrics::ORDERS_SEEN.increment(&metrics::order_pair_labels(event)))
+        .try_filter(move |event| {
+            future::ready((options.include_expired || has_expiration(event)) && is_listed(&order_filter, event))
         })
-        .await?;
+        .take(options.limit.unwrap_or(usize::MAX))
+}
 
-    let events = filter_expired_events(events);
+pub async fn handle(
+    client: &RelayClient,
+    order_filter: OrderFilter,
+    options: &QueryOptions,
+) -> crate::error::Result<Events> {
+    let mut events = Events::default();
+    let mut stream = pin!(handle_stream(client, order_filter, options.clone()));
+    while let Some(event) = stream.try_next().await? {
+        events.insert(event);
+    }
     Ok(events)
 }
 
-#[inline]
-fn filter_expired_events(events_to_filter: Events) -> Events {
-    let time_now = Timestamp::now();
-    events_to_filter
+pub async fn handle_annotated(
+    client: &RelayClient,
+    order_filter: OrderFilter,
+    options: &QueryOptions,
+    maker_registry: Option<&dyn MakerRegistry>,
+    compiler_allow_list: Option<&CompilerAllowList>,
+) -> crate::error::Result<Vec<ListedOrder>> {
+    let registered_only = order_filter.registered_only;
+    let with_reputation = order_filter.with_reputation;
+    let web_of_trust_root = order_filter.web_of_trust_root;
+    let trusted_compilers_only = order_filter.trusted_compilers_only;
+    if registered_only && maker_registry.is_none() {
+        return Err(NostrRelayError::MissingMakerRegistry);
+    }
+    if trusted_compilers_only && compiler_allow_list.is_none() {
+        return Err(NostrRelayError::MissingCompilerAllowList);
+    }
+
+    // Limit applies to orders passing registry and compiler filters, so history is paged until it is reached
+    let limit = options.limit.unwrap_or(usize::MAX);
+    let options = QueryOptions {
+        limit: None,
+        ..options.clone()
+    };
+    let mut events = pin!(handle_stream(client, order_filter, options));
+    let mut registrations: HashMap<PublicKey, Option<bool>> = HashMap::new();
+    let mut orders = Vec::new();
+    while orders.len() < limit {
+        let Some(event) = events.try_next().await? else {
+            break;
+        };
+
+        let maker_registered = match maker_registry {
+            Some(maker_registry) => maker_registration(maker_registry, &mut registrations, event.pubkey).await,
+            None => None,
+        };
+        let order = ListedOrder {
+            maker_registered,
+            maker_reputation: None,
+            compiler_status: compiler_allow_list.map(|x| compiler_status(x, &event)),
+            event,
+        };
+
+        if registered_only && order.maker_registered != Some(true) {
+            continue;
+        }
+        if trusted_compilers_only && order.compiler_status != Some(CompilerStatus::Trusted) {
+            continue;
+        }
+        orders.push(order);
+    }
+
+    if !with_reputation {
+        return Ok(orders);
+    }
+
+    let makers = orders.iter().map(|x| x.event.pubkey).collect::<BTreeSet<PublicKey>>();
+    let reputations = reputation::handle(client, makers, web_of_trust_root).await?;
+    let orders = orders
         .into_iter()
-        .filter(|x| match x.tags.expiration() {
-            None => false,
-            Some(t) => t.as_u64() > time_now.as_u64(),
+        .map(|order| ListedOrder {
+            maker_reputation: reputations.get(&order.event.pubkey).cloned(),
+            ..order
         })
-        .collect()
+        .collect();
+
+    Ok(orders)
+}
+
+/// Registration of the maker, checked once per maker, `None` if the registry failed to answer.
+async fn maker_registration(
+    maker_registry: &dyn MakerRegistry,
+    registrations: &mut HashMap<PublicKey, Option<bool>>,
+    maker: PublicKey,
+) -> Option<bool> {
+    if let Some(registered) = registrations.get(&maker) {
+        return *registered;
+    }
+
+    let registered = match maker_registry.is_registered(&maker).await {
+        Ok(registered) => Some(registered),
+        Err(err) => {
+            tracing::warn!(maker = %maker, error = %err, "Failed to check maker registration");
+            None
+        }
+    };
+    registrations.insert(maker, registered);
+    registered
+}
+
+/// Orders with missing or malformed `compiler` tag are treated as compiled by unknown toolchain.
+fn compiler_status(compiler_allow_list: &CompilerAllowList, event: &Event) -> CompilerStatus {
+    match OrderPlaceEventTags::try_from(event) {
+        Ok(tags) => compiler_allow_list.status(&tags.compiler_name, &tags.compiler_build_hash),
+        Err(err) => {
+            tracing::debug!(event_id = %event.id, error = %err, "Failed to parse order");
+            CompilerStatus::Unknown
+        }
+    }
+}
+
+#[inline]
+fn is_listed(order_filter: &OrderFilter, event: &Event) -> bool {
+    order_filter
+        .min_pow_difficulty
+        .is_none_or(|min_difficulty| pow::meets_difficulty(event, min_difficulty))
+        && order_filter
+            .option_terms
+            .as_ref()
+            .is_none_or(|option_filter| matches_option_terms(event, option_filter))
+}
+
+/// Live orders always expire, expiration itself is checked by [`QueryOptions`].
+#[inline]
+fn has_expiration(event: &Event) -> bool {
+    event.tags.expiration().is_some()
+}
+
+/// Orders with malformed option terms never match the filter.
+#[inline]
+fn matches_option_terms(event: &Event, option_filter: &OptionTermsFilter) -> bool {
+    match OptionTerms::from_event(event) {
+        Ok(terms) => terms.is_some_and(|terms| option_filter.matches(&terms)),
+        Err(err) => {
+            tracing::debug!(event_id = %event.id, error = %err, "Failed to parse option terms");
+            false
+        }
+    }
 }
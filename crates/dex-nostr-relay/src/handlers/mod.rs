@@ -1,5 +1,15 @@
+pub(crate) mod accept_reply;
+pub(crate) mod archive;
+pub(crate) mod cancel_orders;
 pub(crate) mod get_events;
+pub(crate) mod inspect_order;
+pub(crate) mod inspect_reply;
 pub(crate) mod list_orders;
+pub(crate) mod oracle_attestations;
 pub(crate) mod order_replies;
 pub(crate) mod place_order;
+pub(crate) mod quotes;
+pub(crate) mod relay_list;
 pub(crate) mod reply_order;
+pub(crate) mod reputation;
+pub(crate) mod settle_order;
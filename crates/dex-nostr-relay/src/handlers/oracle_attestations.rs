@@ -0,0 +1,64 @@
+use crate::oracle::{AttestationQuery, OracleSet, PriceAttestation, PriceAttestationTags};
+use crate::pow;
+use crate::relay_client::RelayClient;
+use crate::types::{BLOCKSTREAM_ORACLE_ATTESTATION_CONTENT, CustomKind, OracleAttestationKind};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::{EventBuilder, EventId, Filter, Timestamp};
+
+pub async fn publish(client: &RelayClient, tags: PriceAttestationTags) -> crate::error::Result<EventId> {
+    let client_signer = client.get_signer().await?;
+    let client_pubkey = client_signer.get_public_key().await?;
+
+    let attestation = EventBuilder::new(
+        OracleAttestationKind::get_kind(),
+        BLOCKSTREAM_ORACLE_ATTESTATION_CONTENT,
+    )
+    .tags(tags.to_tags())
+    .custom_created_at(Timestamp::now());
+
+    let attestation = pow::build_event(attestation, client_pubkey, client.pow_difficulty()).await?;
+    let attestation = client_signer.sign_event(attestation).await?;
+
+    let event_id = client.publish_event(&attestation).await?;
+
+    Ok(event_id)
+}
+
+/// Fetches attestations of the oracle set matching the query, malformed attestations are skipped.
+///
+/// Attestations are published after the observation, so only the lower bound of creation time is used.
+pub async fn handle(
+    client: &RelayClient,
+    oracle_set: &OracleSet,
+    query: &AttestationQuery,
+) -> crate::error::Result<Vec<PriceAttestation>> {
+    let since = query.timestamp.as_u64().saturating_sub(query.tolerance.as_secs());
+    let events = client
+        .req_and_wait(Filter {
+            ids: None,
+            authors: Some(oracle_set.oracles().clone()),
+            kinds: Some(BTreeSet::from([OracleAttestationKind::get_kind()])),
+            search: None,
+            since: Some(Timestamp::from(since)),
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        })
+        .await?;
+
+    let attestations = events
+        .iter()
+        .filter_map(|event| match PriceAttestation::try_from(event) {
+            Ok(attestation) => Some(attestation),
+            Err(err) => {
+                tracing::debug!(event_id = %event.id, error = %err, "Failed to parse price attestation");
+                None
+            }
+        })
+        .filter(|x| oracle_set.is_trusted(&x.oracle) && query.matches(x))
+        .collect();
+
+    Ok(attestations)
+}
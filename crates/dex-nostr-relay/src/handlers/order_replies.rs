@@ -1,3 +1,4 @@
+use crate::query::{self, QueryOptions};
 use crate::relay_client::RelayClient;
 use crate::types::{CustomKind, TakerOrderKind};
 
@@ -6,9 +7,10 @@ use std::collections::{BTreeMap, BTreeSet};
 use nostr::{EventId, Filter, SingleLetterTag};
 use nostr_sdk::prelude::Events;
 
-pub async fn handle(client: &RelayClient, event_id: EventId) -> crate::error::Result<Events> {
-    let events = client
-        .req_and_wait(Filter {
+pub async fn handle(client: &RelayClient, event_id: EventId, options: &QueryOptions) -> crate::error::Result<Events> {
+    let events = query::query_events(
+        client,
+        Filter {
             ids: None,
             authors: None,
             kinds: Some(BTreeSet::from([TakerOrderKind::get_kind()])),
@@ -17,7 +19,9 @@ pub async fn handle(client: &RelayClient, event_id: EventId) -> crate::error::Re
             until: None,
             limit: None,
             generic_tags: BTreeMap::from([(SingleLetterTag::from_char('e')?, BTreeSet::from([event_id.to_string()]))]),
-        })
-        .await?;
+        },
+        options,
+    )
+    .await?;
     Ok(events)
 }
@@ -1,21 +1,66 @@
+use crate::batch::{BatchPlacementReport, FailedPlacement};
+use crate::compiler_provenance::validate_compiler_tag;
+use crate::contract_verification;
+use crate::metrics;
+use crate::pow;
 use crate::relay_client::RelayClient;
 use crate::relay_processor::OrderPlaceEventTags;
 use crate::types::{BLOCKSTREAM_MAKER_CONTENT, CustomKind, MAKER_EXPIRATION_TIME, MakerOrderKind};
 
 use std::borrow::Cow;
+use std::time::Duration;
 
-use nostr::{EventBuilder, EventId, Tag, TagKind, Timestamp};
+use futures_util::future::join_all;
+use nostr::{Event, EventBuilder, EventId, PublicKey, Tag, TagKind, Timestamp, UnsignedEvent};
 
 pub async fn handle(client: &RelayClient, tags: OrderPlaceEventTags) -> crate::error::Result<EventId> {
     let client_signer = client.get_signer().await?;
     let client_pubkey = client_signer.get_public_key().await?;
 
+    let unsigned_event = build(
+        tags,
+        client_pubkey,
+        client.pow_difficulty(),
+        Duration::from_secs(MAKER_EXPIRATION_TIME),
+    )
+    .await?;
+    let signed_event = client_signer.sign_event(unsigned_event).await?;
+
+    publish(client, &signed_event).await
+}
+
+/// Builds unsigned maker order authored by `maker_pubkey`, which expires in `expires_in`.
+pub async fn build(
+    tags: OrderPlaceEventTags,
+    maker_pubkey: PublicKey,
+    pow_difficulty: u8,
+    expires_in: Duration,
+) -> crate::error::Result<UnsignedEvent> {
+    validate_compiler_tag(&tags.compiler_name, &tags.compiler_build_hash)?;
+
+    let mut contract_tags = Vec::new();
+    if let Some(program) = tags.contract_program {
+        contract_verification::decode_program(&program)?;
+        contract_tags.push(Tag::custom(TagKind::Custom(Cow::from("contract")), [program]));
+    }
+    if let Some(cmr) = tags.contract_cmr {
+        contract_tags.push(Tag::custom(TagKind::Custom(Cow::from("cmr")), [cmr]));
+    }
+    if let Some(address) = tags.contract_address {
+        contract_tags.push(Tag::custom(TagKind::Custom(Cow::from("contract_address")), [address]));
+    }
+
     let timestamp_now = Timestamp::now();
 
+    if let Some(option_terms) = &tags.option_terms {
+        option_terms.validate_for_placement(timestamp_now)?;
+        contract_tags.extend(option_terms.to_tags());
+    }
+
     let maker_order = EventBuilder::new(MakerOrderKind::get_kind(), BLOCKSTREAM_MAKER_CONTENT)
         .tags([
-            Tag::public_key(client_pubkey),
-            Tag::expiration(Timestamp::from(timestamp_now.as_u64() + MAKER_EXPIRATION_TIME)),
+            Tag::public_key(maker_pubkey),
+            Tag::expiration(timestamp_now + expires_in),
             Tag::custom(
                 TagKind::Custom(Cow::from("compiler")),
                 [tags.compiler_name, tags.compiler_build_hash],
@@ -24,12 +69,69 @@ pub async fn handle(client: &RelayClient, tags: OrderPlaceEventTags) -> crate::e
             Tag::custom(TagKind::Custom(Cow::from("asset_to_sell")), [tags.asset_to_sell]),
             Tag::custom(TagKind::Custom(Cow::from("price")), [tags.price.to_string()]),
         ])
+        .tags(
+            tags.amount
+                .map(|amount| Tag::custom(TagKind::Custom(Cow::from("amount")), [amount.to_string()])),
+        )
+        .tags((tags.expiry > 0).then(|| Tag::custom(TagKind::Custom(Cow::from("expiry")), [tags.expiry.to_string()])))
+        .tags(contract_tags)
         .custom_created_at(timestamp_now);
 
-    let text_note = maker_order.build(client_pubkey);
-    let signed_event = client_signer.sign_event(text_note).await?;
+    pow::build_event(maker_order, maker_pubkey, pow_difficulty).await
+}
 
-    let maker_order_event_id = client.publish_event(&signed_event).await?;
+/// Publishes signed maker order, relays are checked for expiration support.
+pub async fn publish(client: &RelayClient, signed_event: &Event) -> crate::error::Result<EventId> {
+    let maker_order_event_id = client.publish_expiring_event(signed_event).await?;
+    metrics::ORDERS_PLACED.increment(&metrics::order_pair_labels(signed_event));
 
     Ok(maker_order_event_id)
 }
+
+/// Signs and publishes orders concurrently, failures of single orders are collected into the report.
+pub async fn handle_batch(
+    client: &RelayClient,
+    orders: Vec<OrderPlaceEventTags>,
+) -> crate::error::Result<BatchPlacementReport> {
+    // Fail early instead of reporting the same signer error for every order
+    client.get_signer().await?;
+
+    let results = join_all(orders.into_iter().map(|tags| handle(client, tags))).await;
+
+    let mut report = BatchPlacementReport::default();
+    for (index, result) in results.into_iter().enumerate() {
+        match result {
+            Ok(event_id) => report.placed.push((index, event_id)),
+            Err(err) => {
+                tracing::warn!(index, error = %err, "Failed to place order of the batch");
+                report.failed.push(FailedPlacement {
+                    index,
+                    err_msg: err.to_string(),
+                });
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Places the batch and withdraws already published orders if any of them failed.
+///
+/// Failed withdrawal is recorded in the report instead of failing the whole call, so the caller
+/// still learns which orders are live.
+pub async fn handle_batch_all_or_nothing(
+    client: &RelayClient,
+    orders: Vec<OrderPlaceEventTags>,
+) -> crate::error::Result<BatchPlacementReport> {
+    let mut report = handle_batch(client, orders).await?;
+    if !report.is_complete() && !report.placed.is_empty() {
+        let placed = report.placed.iter().map(|(_, event_id)| *event_id);
+        let cancellation = super::cancel_orders::handle(client, placed, "order batch was placed partially").await;
+        if let Err(err) = &cancellation {
+            tracing::error!(error = %err, "Failed to withdraw partially placed order batch");
+        }
+        report.cancellation = Some(cancellation.map_err(|err| err.to_string()));
+    }
+
+    Ok(report)
+}
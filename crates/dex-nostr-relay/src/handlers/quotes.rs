@@ -0,0 +1,168 @@
+use crate::error::NostrRelayError;
+use crate::pow;
+use crate::query::{self, QueryOptions};
+use crate::relay_client::RelayClient;
+use crate::rfq::{ENCRYPTED_QUOTE_SCHEME, Quote, QuoteRequest, QuoteRequestTags, QuoteTags};
+use crate::types::{
+    BLOCKSTREAM_QUOTE_CONTENT, BLOCKSTREAM_QUOTE_REQUEST_CONTENT, CustomKind, QuoteKind, QuoteRequestKind,
+};
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::{EventBuilder, EventId, Filter, SingleLetterTag, Tag, TagKind, Timestamp};
+
+pub async fn request(client: &RelayClient, tags: QuoteRequestTags) -> crate::error::Result<EventId> {
+    tags.validate()?;
+
+    let client_signer = client.get_signer().await?;
+    let client_pubkey = client_signer.get_public_key().await?;
+
+    let timestamp_now = Timestamp::now();
+
+    let quote_request = EventBuilder::new(QuoteRequestKind::get_kind(), BLOCKSTREAM_QUOTE_REQUEST_CONTENT)
+        .tags([Tag::expiration(timestamp_now + tags.expires_in)])
+        .tags(tags.to_tags())
+        .custom_created_at(timestamp_now);
+
+    let quote_request = pow::build_event(quote_request, client_pubkey, client.pow_difficulty()).await?;
+    let quote_request = client_signer.sign_event(quote_request).await?;
+
+    client.publish_expiring_event(&quote_request).await
+}
+
+/// Lists active quote requests, expired and malformed requests are skipped.
+pub async fn list_requests(client: &RelayClient, options: &QueryOptions) -> crate::error::Result<Vec<QuoteRequest>> {
+    let events = query::query_events(
+        client,
+        Filter {
+            ids: None,
+            authors: None,
+            kinds: Some(BTreeSet::from([QuoteRequestKind::get_kind()])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        },
+        options,
+    )
+    .await?;
+
+    let requests = events
+        .iter()
+        .filter_map(|event| match QuoteRequest::try_from(event) {
+            Ok(request) => Some(request),
+            Err(err) => {
+                tracing::debug!(event_id = %event.id, error = %err, "Failed to parse quote request");
+                None
+            }
+        })
+        .collect();
+
+    Ok(requests)
+}
+
+/// Responds to the quote request, quote expires not later than the request itself.
+pub async fn quote(client: &RelayClient, request_id: EventId, tags: QuoteTags) -> crate::error::Result<EventId> {
+    let client_signer = client.get_signer().await?;
+    let client_pubkey = client_signer.get_public_key().await?;
+
+    let request = client
+        .req_and_wait(Filter {
+            ids: Some(BTreeSet::from([request_id])),
+            authors: None,
+            kinds: Some(BTreeSet::from([QuoteRequestKind::get_kind()])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        })
+        .await?
+        .first_owned()
+        .ok_or(NostrRelayError::EventNotFound(request_id))?;
+    if request.is_expired() {
+        return Err(NostrRelayError::ExpiredEvent(request_id));
+    }
+    tags.terms.validate_for(&QuoteRequest::try_from(&request)?)?;
+
+    let timestamp_now = Timestamp::now();
+    let expiration = request.tags.expiration().map_or(timestamp_now + tags.expires_in, |x| {
+        (*x).min(timestamp_now + tags.expires_in)
+    });
+
+    let (content, terms_tags) = if tags.encrypted {
+        let terms = serde_json::to_string(&tags.terms).expect("quote terms are serializable");
+        let content = client_signer.nip44_encrypt(&request.pubkey, &terms).await?;
+        let marker = Tag::custom(TagKind::Custom(Cow::from("encrypted")), [ENCRYPTED_QUOTE_SCHEME]);
+        (content, vec![marker])
+    } else {
+        (BLOCKSTREAM_QUOTE_CONTENT.to_string(), tags.terms.to_tags())
+    };
+
+    let quote = EventBuilder::new(QuoteKind::get_kind(), content)
+        .tags([
+            Tag::public_key(request.pubkey),
+            Tag::event(request_id),
+            Tag::expiration(expiration),
+        ])
+        .tags(terms_tags)
+        .custom_created_at(timestamp_now);
+
+    let quote = pow::build_event(quote, client_pubkey, client.pow_difficulty()).await?;
+    let quote = client_signer.sign_event(quote).await?;
+
+    client.publish_expiring_event(&quote).await
+}
+
+/// Lists active quotes for the request, encrypted quotes are decrypted if the client has a signer.
+pub async fn list(
+    client: &RelayClient,
+    request_id: EventId,
+    options: &QueryOptions,
+) -> crate::error::Result<Vec<Quote>> {
+    let events = query::query_events(
+        client,
+        Filter {
+            ids: None,
+            authors: None,
+            kinds: Some(BTreeSet::from([QuoteKind::get_kind()])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::from([(
+                SingleLetterTag::from_char('e')?,
+                BTreeSet::from([request_id.to_string()]),
+            )]),
+        },
+        options,
+    )
+    .await?;
+    let client_signer = client.get_signer().await.ok();
+
+    let mut quotes = Vec::new();
+    for event in events.iter() {
+        let decrypted_content = match &client_signer {
+            Some(signer) if Quote::is_encrypted_event(event) => {
+                match signer.nip44_decrypt(&event.pubkey, &event.content).await {
+                    Ok(content) => Some(content),
+                    Err(err) => {
+                        tracing::debug!(event_id = %event.id, error = %err, "Failed to decrypt quote");
+                        continue;
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        match Quote::from_event(event, decrypted_content.as_deref()) {
+            Ok(quote) if quote.request_id == request_id => quotes.push(quote),
+            Ok(_) => {}
+            Err(err) => tracing::debug!(event_id = %event.id, error = %err, "Failed to parse quote"),
+        }
+    }
+
+    Ok(quotes)
+}
@@ -0,0 +1,40 @@
+use crate::relay_client::RelayClient;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use nostr::nips::nip65::{RelayMetadata, extract_owned_relay_list};
+use nostr::{Filter, Kind, PublicKey, RelayUrl};
+
+/// Fetches the latest NIP-65 relay list of the author and returns relays suitable for the given usage.
+///
+/// Relays without read/write marker are used for both directions, `None` usage returns all relays.
+pub async fn handle(
+    client: &RelayClient,
+    author: PublicKey,
+    usage: Option<RelayMetadata>,
+) -> crate::error::Result<Vec<RelayUrl>> {
+    let events = client
+        .req_and_wait(Filter {
+            ids: None,
+            authors: Some(BTreeSet::from([author])),
+            kinds: Some(BTreeSet::from([Kind::RelayList])),
+            search: None,
+            since: None,
+            until: None,
+            limit: Some(1),
+            generic_tags: BTreeMap::default(),
+        })
+        .await?;
+
+    let Some(relay_list) = events.first_owned() else {
+        tracing::debug!(author = %author, "No relay list found for author");
+        return Ok(Vec::new());
+    };
+
+    let relays = extract_owned_relay_list(relay_list)
+        .filter(|(_, metadata)| usage.is_none() || metadata.is_none() || *metadata == usage)
+        .map(|(url, _)| url)
+        .collect();
+
+    Ok(relays)
+}
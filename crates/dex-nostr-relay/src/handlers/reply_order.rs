@@ -1,10 +1,13 @@
+use crate::pow;
 use crate::relay_client::RelayClient;
 use crate::relay_processor::OrderReplyEventTags;
 use crate::types::{BLOCKSTREAM_TAKER_CONTENT, CustomKind, TakerOrderKind};
 
 use std::borrow::Cow;
+use std::str::FromStr;
 
-use nostr::{EventBuilder, EventId, NostrSigner, PublicKey, Tag, TagKind, Timestamp};
+use elements::pset::PartiallySignedTransaction;
+use nostr::{Event, EventBuilder, EventId, NostrSigner, PublicKey, Tag, TagKind, Timestamp, UnsignedEvent};
 
 pub async fn handle(
     client: &RelayClient,
@@ -15,21 +18,50 @@ pub async fn handle(
     let client_signer = client.get_signer().await?;
     let client_pubkey = client_signer.get_public_key().await?;
 
+    let unsigned_event = build(
+        maker_event_id,
+        maker_pubkey,
+        tags,
+        client_pubkey,
+        client.pow_difficulty(),
+    )
+    .await?;
+    let reply_event = client_signer.sign_event(unsigned_event).await?;
+
+    publish(client, &reply_event).await
+}
+
+/// Builds unsigned taker reply authored by `taker_pubkey`.
+pub async fn build(
+    maker_event_id: EventId,
+    maker_pubkey: PublicKey,
+    tags: OrderReplyEventTags,
+    taker_pubkey: PublicKey,
+    pow_difficulty: u8,
+) -> crate::error::Result<UnsignedEvent> {
+    let mut swap_tags = Vec::new();
+    if let Some(pset) = tags.swap_pset {
+        PartiallySignedTransaction::from_str(&pset)?;
+        swap_tags.push(Tag::custom(TagKind::Custom(Cow::from("pset")), [pset]));
+    }
+
     let timestamp_now = Timestamp::now();
 
     let taker_response = EventBuilder::new(TakerOrderKind::get_kind(), BLOCKSTREAM_TAKER_CONTENT)
         .tags([
-            Tag::public_key(client_pubkey),
+            Tag::public_key(taker_pubkey),
             Tag::event(maker_event_id),
             Tag::custom(TagKind::Custom(Cow::from("maker_pubkey")), [maker_pubkey]),
-            Tag::custom(TagKind::Custom(Cow::from("tx_id")), [tags.tx_id]),
         ])
+        .tags((!tags.tx_id.is_empty()).then(|| Tag::custom(TagKind::Custom(Cow::from("tx_id")), [tags.tx_id])))
+        .tags(swap_tags)
         .custom_created_at(timestamp_now);
 
-    let reply_event = taker_response.build(client_pubkey);
-    let reply_event = client_signer.sign_event(reply_event).await?;
+    pow::build_event(taker_response, taker_pubkey, pow_difficulty).await
+}
 
-    let event_id = client.publish_event(&reply_event).await?;
+pub async fn publish(client: &RelayClient, reply_event: &Event) -> crate::error::Result<EventId> {
+    let event_id = client.publish_event(reply_event).await?;
 
     Ok(event_id)
 }
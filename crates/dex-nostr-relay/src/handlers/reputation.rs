@@ -0,0 +1,92 @@
+use crate::relay_client::RelayClient;
+use crate::reputation::{MakerReputation, compute_reputation};
+use crate::types::{CustomKind, MakerAcceptKind, MakerOrderKind, TakerOrderKind};
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use nostr::{Event, Filter, Kind, PublicKey, SingleLetterTag};
+
+pub async fn handle(
+    client: &RelayClient,
+    makers: BTreeSet<PublicKey>,
+    web_of_trust_root: Option<PublicKey>,
+) -> crate::error::Result<HashMap<PublicKey, MakerReputation>> {
+    if makers.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let mut events = client
+        .req_and_wait(Filter {
+            ids: None,
+            authors: Some(makers.clone()),
+            kinds: Some(BTreeSet::from([
+                MakerOrderKind::get_kind(),
+                MakerAcceptKind::get_kind(),
+                Kind::EventDeletion,
+            ])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        })
+        .await?
+        .to_vec();
+
+    let order_ids = events
+        .iter()
+        .filter(|x| x.kind == MakerOrderKind::get_kind())
+        .map(|x| x.id.to_string())
+        .collect::<BTreeSet<String>>();
+
+    if !order_ids.is_empty() {
+        let replies = client
+            .req_and_wait(Filter {
+                ids: None,
+                authors: None,
+                kinds: Some(BTreeSet::from([TakerOrderKind::get_kind()])),
+                search: None,
+                since: None,
+                until: None,
+                limit: None,
+                generic_tags: BTreeMap::from([(SingleLetterTag::from_char('e')?, order_ids)]),
+            })
+            .await?;
+        events.extend(replies);
+    }
+
+    let trusted_makers = match web_of_trust_root {
+        None => None,
+        Some(root) => Some(fetch_follow_list(client, root).await?),
+    };
+
+    Ok(makers
+        .into_iter()
+        .map(|maker| (maker, compute_reputation(maker, &events, trusted_makers.as_ref())))
+        .collect())
+}
+
+/// Returns public keys followed by the root (NIP-02 contact list), including the root itself.
+async fn fetch_follow_list(client: &RelayClient, root: PublicKey) -> crate::error::Result<HashSet<PublicKey>> {
+    let contact_list = client
+        .req_and_wait(Filter {
+            ids: None,
+            authors: Some(BTreeSet::from([root])),
+            kinds: Some(BTreeSet::from([Kind::ContactList])),
+            search: None,
+            since: None,
+            until: None,
+            limit: Some(1),
+            generic_tags: BTreeMap::default(),
+        })
+        .await?
+        .first_owned();
+
+    let mut follows = contact_list
+        .as_ref()
+        .map(|x: &Event| x.tags.public_keys().copied().collect::<HashSet<PublicKey>>())
+        .unwrap_or_default();
+    follows.insert(root);
+
+    Ok(follows)
+}
@@ -0,0 +1,138 @@
+use crate::error::NostrRelayError;
+use crate::handlers::oracle_attestations;
+use crate::oracle::{AttestationQuery, OracleSet};
+use crate::pow;
+use crate::relay_client::RelayClient;
+use crate::relay_processor::{OrderPlaceEventTags, OrderReplyEventTags};
+use crate::settlement::{
+    OrderThread, SettlementParams, SettlementResult, SettlementRole, build_settlement_pset, compute_payout,
+    settlement_timestamp,
+};
+use crate::tags::required_tag_value;
+use crate::types::{BLOCKSTREAM_SETTLEMENT_CONTENT, CustomKind, MakerOrderKind, SettlementKind, TakerOrderKind};
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use nostr::{EventBuilder, EventId, Filter, Kind, PublicKey, Tag, TagKind, Timestamp};
+use nostr_sdk::prelude::Events;
+
+pub async fn handle(
+    client: &RelayClient,
+    oracle_set: &OracleSet,
+    role: SettlementRole,
+    reply_event_id: EventId,
+    params: SettlementParams,
+) -> crate::error::Result<SettlementResult> {
+    let client_signer = client.get_signer().await?;
+    let client_pubkey = client_signer.get_public_key().await?;
+
+    let thread = load_thread(client, reply_event_id).await?;
+    let (party, counterparty) = match role {
+        SettlementRole::Maker => (thread.order.pubkey, thread.reply.pubkey),
+        SettlementRole::Taker => (thread.reply.pubkey, thread.order.pubkey),
+    };
+    if party != client_pubkey {
+        return Err(NostrRelayError::InvalidSettlement {
+            err_msg: format!("client key is not the {role} of the order"),
+        });
+    }
+    let terms = thread
+        .order_tags
+        .option_terms
+        .as_ref()
+        .ok_or_else(|| NostrRelayError::InvalidSettlement {
+            err_msg: "order is not an option".to_string(),
+        })?;
+
+    let timestamp_now = Timestamp::now();
+    let query = AttestationQuery {
+        base_asset: terms.collateral_asset.clone(),
+        quote_asset: terms.settlement_asset.clone(),
+        timestamp: settlement_timestamp(terms, role, timestamp_now)?,
+        tolerance: params.oracle_tolerance,
+    };
+    let attestations = oracle_attestations::handle(client, oracle_set, &query).await?;
+    let oracle_price = oracle_set.aggregate(&attestations, query.timestamp)?;
+
+    let payout = compute_payout(terms, params.collateral_amount, oracle_price.price)?;
+    let pset = build_settlement_pset(&thread, role, &params, payout)?;
+    let proposed_txid = pset.extract_tx()?.txid();
+
+    let attestation_ids = oracle_price.attestations.iter().map(ToString::to_string);
+    let settlement = EventBuilder::new(SettlementKind::get_kind(), BLOCKSTREAM_SETTLEMENT_CONTENT)
+        .tags([
+            Tag::public_key(counterparty),
+            Tag::event(thread.order.id),
+            Tag::custom(TagKind::Custom(Cow::from("reply_event_id")), [reply_event_id]),
+            Tag::custom(TagKind::Custom(Cow::from("proposed_txid")), [proposed_txid.to_string()]),
+            Tag::custom(TagKind::Custom(Cow::from("role")), [role.to_string()]),
+            Tag::custom(
+                TagKind::Custom(Cow::from("oracle_price")),
+                [oracle_price.price.to_string()].into_iter().chain(attestation_ids),
+            ),
+        ])
+        .custom_created_at(timestamp_now);
+
+    let settlement = pow::build_event(settlement, client_pubkey, client.pow_difficulty()).await?;
+    let settlement = client_signer.sign_event(settlement).await?;
+
+    let event_id = client.publish_event(&settlement).await?;
+
+    Ok(SettlementResult {
+        event_id,
+        proposed_txid,
+        pset: pset.to_string(),
+        oracle_price,
+        payout,
+    })
+}
+
+/// Loads taker reply and the maker order it refers to.
+///
+/// The order is the maker order among the events referenced by the reply, authored by the maker
+/// named in the `maker_pubkey` tag of the reply.
+pub(crate) async fn load_thread(client: &RelayClient, reply_event_id: EventId) -> crate::error::Result<OrderThread> {
+    let reply = fetch_events(client, BTreeSet::from([reply_event_id]), TakerOrderKind::get_kind())
+        .await?
+        .first_owned()
+        .ok_or(NostrRelayError::EventNotFound(reply_event_id))?;
+    let maker_pubkey = required_tag_value(&reply, "maker_pubkey", 1)?;
+    let maker_pubkey = PublicKey::from_str(maker_pubkey).map_err(|err| NostrRelayError::InvalidOrderTag {
+        tag: "maker_pubkey".to_string(),
+        err_msg: format!("'{maker_pubkey}' is not a valid public key, {err}"),
+    })?;
+
+    let referenced = reply.tags.event_ids().copied().collect::<BTreeSet<EventId>>();
+    if referenced.is_empty() {
+        return Err(NostrRelayError::EventNotFound(reply_event_id));
+    }
+    let order = fetch_events(client, referenced.clone(), MakerOrderKind::get_kind())
+        .await?
+        .into_iter()
+        .find(|x| referenced.contains(&x.id) && x.pubkey == maker_pubkey)
+        .ok_or(NostrRelayError::EventNotFound(reply_event_id))?;
+
+    Ok(OrderThread {
+        order_tags: OrderPlaceEventTags::try_from(&order)?,
+        reply_tags: OrderReplyEventTags::try_from(&reply)?,
+        order,
+        reply,
+    })
+}
+
+async fn fetch_events(client: &RelayClient, ids: BTreeSet<EventId>, kind: Kind) -> crate::error::Result<Events> {
+    client
+        .req_and_wait(Filter {
+            ids: Some(ids),
+            authors: None,
+            kinds: Some(BTreeSet::from([kind])),
+            search: None,
+            since: None,
+            until: None,
+            limit: None,
+            generic_tags: BTreeMap::default(),
+        })
+        .await
+}
@@ -0,0 +1,252 @@
+use crate::contract_verification;
+use crate::oracle::PriceAttestation;
+use crate::relay_processor::{OrderPlaceEventTags, OrderReplyEventTags};
+use crate::rfq::{Quote, QuoteRequest};
+use crate::tags::tag_value;
+use crate::types::{
+    CustomKind, MakerAcceptKind, MakerOrderKind, OracleAttestationKind, QuoteKind, QuoteRequestKind, SettlementKind,
+    TakerOrderKind,
+};
+
+use std::fmt;
+use std::str::FromStr;
+
+use elements::AssetId;
+use nostr::{Event, EventId, Kind, PublicKey, Timestamp};
+
+/// DEX role of the event, derived from its kind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DexEventKind {
+    MakerOrder,
+    TakerReply,
+    MakerAccept,
+    OracleAttestation,
+    Settlement,
+    QuoteRequest,
+    Quote,
+    Deletion,
+    Unknown(u16),
+}
+
+/// Typed representation of the event, if its kind has one.
+#[derive(Debug, Clone)]
+pub enum DecodedEvent {
+    Order(Box<OrderPlaceEventTags>),
+    Reply(OrderReplyEventTags),
+    Attestation(PriceAttestation),
+    QuoteRequest(QuoteRequest),
+    Quote(Quote),
+}
+
+/// Result of checking an event against the DEX protocol.
+#[derive(Debug, Clone)]
+pub struct EventInspection {
+    pub event_id: EventId,
+    pub author: PublicKey,
+    pub kind: DexEventKind,
+    pub id_valid: bool,
+    pub signature_valid: bool,
+    pub decoded: Option<DecodedEvent>,
+    /// Human-readable protocol violations, empty for a valid event
+    pub violations: Vec<String>,
+}
+
+impl From<Kind> for DexEventKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            kind if kind == MakerOrderKind::get_kind() => DexEventKind::MakerOrder,
+            kind if kind == TakerOrderKind::get_kind() => DexEventKind::TakerReply,
+            kind if kind == MakerAcceptKind::get_kind() => DexEventKind::MakerAccept,
+            kind if kind == OracleAttestationKind::get_kind() => DexEventKind::OracleAttestation,
+            kind if kind == SettlementKind::get_kind() => DexEventKind::Settlement,
+            kind if kind == QuoteRequestKind::get_kind() => DexEventKind::QuoteRequest,
+            kind if kind == QuoteKind::get_kind() => DexEventKind::Quote,
+            Kind::EventDeletion => DexEventKind::Deletion,
+            kind => DexEventKind::Unknown(kind.as_u16()),
+        }
+    }
+}
+
+impl fmt::Display for DexEventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DexEventKind::MakerOrder => write!(f, "maker order"),
+            DexEventKind::TakerReply => write!(f, "taker reply"),
+            DexEventKind::MakerAccept => write!(f, "maker accept"),
+            DexEventKind::OracleAttestation => write!(f, "oracle attestation"),
+            DexEventKind::Settlement => write!(f, "settlement"),
+            DexEventKind::QuoteRequest => write!(f, "quote request"),
+            DexEventKind::Quote => write!(f, "quote"),
+            DexEventKind::Deletion => write!(f, "deletion"),
+            DexEventKind::Unknown(kind) => write!(f, "unknown kind {kind}"),
+        }
+    }
+}
+
+fn check_asset(violations: &mut Vec<String>, tag: &str, asset: &str) {
+    if let Err(err) = AssetId::from_str(asset) {
+        violations.push(format!("tag '{tag}': '{asset}' is not a valid asset id, {err}"));
+    }
+}
+
+fn check_event_reference(violations: &mut Vec<String>, event: &Event) {
+    if event.tags.event_ids().next().is_none() {
+        violations.push("tag 'e': event does not reference another event".to_string());
+    }
+}
+
+fn decode_order(event: &Event, violations: &mut Vec<String>) -> Option<DecodedEvent> {
+    let tags = match OrderPlaceEventTags::try_from(event) {
+        Ok(tags) => tags,
+        Err(err) => {
+            violations.push(err.to_string());
+            return None;
+        }
+    };
+
+    if event.tags.expiration().is_none() {
+        violations.push("tag 'expiration': maker order must expire (NIP-40)".to_string());
+    }
+    check_asset(violations, "asset_to_sell", &tags.asset_to_sell);
+    check_asset(violations, "asset_to_buy", &tags.asset_to_buy);
+    if tags.price == 0 {
+        violations.push("tag 'price': price must be positive".to_string());
+    }
+    if tags.contract_program.is_some() {
+        match contract_verification::verify_order(&tags) {
+            Ok(summary) if !summary.is_verified() => {
+                violations.push("contract does not match advertised CMR or address".to_string());
+            }
+            Ok(_) => {}
+            Err(err) => violations.push(err.to_string()),
+        }
+    }
+    if let Some(Err(err)) = tags.option_terms.as_ref().map(|x| x.validate()) {
+        violations.push(err.to_string());
+    }
+
+    Some(DecodedEvent::Order(Box::new(tags)))
+}
+
+fn decode_reply(event: &Event, violations: &mut Vec<String>) -> Option<DecodedEvent> {
+    check_event_reference(violations, event);
+    if tag_value(event, "maker_pubkey", 1).is_none() {
+        violations.push("tag 'maker_pubkey': missing value at position 1".to_string());
+    }
+
+    match OrderReplyEventTags::try_from(event) {
+        Ok(tags) => {
+            if tags.tx_id.is_empty() && tags.swap_pset.is_none() {
+                violations.push("reply carries neither 'tx_id' nor 'pset'".to_string());
+            }
+            Some(DecodedEvent::Reply(tags))
+        }
+        Err(err) => {
+            violations.push(err.to_string());
+            None
+        }
+    }
+}
+
+/// Verifies the event and decodes it according to its DEX kind, `now` is used for expiration.
+pub fn inspect_event(event: &Event, now: Timestamp) -> EventInspection {
+    let kind = DexEventKind::from(event.kind);
+    let id_valid = event.verify_id();
+    let signature_valid = event.verify_signature();
+
+    let mut violations = Vec::new();
+    if !id_valid {
+        violations.push("id does not match event content".to_string());
+    }
+    if !signature_valid {
+        violations.push("signature is invalid".to_string());
+    }
+    if let Some(expiration) = event.tags.expiration()
+        && event.is_expired_at(&now)
+    {
+        violations.push(format!("event expired at {expiration}"));
+    }
+
+    let decoded = match kind {
+        DexEventKind::MakerOrder => decode_order(event, &mut violations),
+        DexEventKind::TakerReply => decode_reply(event, &mut violations),
+        DexEventKind::OracleAttestation => match PriceAttestation::try_from(event) {
+            Ok(attestation) => Some(DecodedEvent::Attestation(attestation)),
+            Err(err) => {
+                violations.push(err.to_string());
+                None
+            }
+        },
+        DexEventKind::QuoteRequest => match QuoteRequest::try_from(event) {
+            Ok(request) => {
+                check_asset(&mut violations, "asset_to_sell", &request.asset_to_sell);
+                check_asset(&mut violations, "asset_to_buy", &request.asset_to_buy);
+                Some(DecodedEvent::QuoteRequest(request))
+            }
+            Err(err) => {
+                violations.push(err.to_string());
+                None
+            }
+        },
+        // Terms of encrypted quotes are readable only by the requester
+        DexEventKind::Quote if Quote::is_encrypted_event(event) => {
+            check_event_reference(&mut violations, event);
+            None
+        }
+        DexEventKind::Quote => match Quote::from_event(event, None) {
+            Ok(quote) => Some(DecodedEvent::Quote(quote)),
+            Err(err) => {
+                violations.push(err.to_string());
+                None
+            }
+        },
+        DexEventKind::MakerAccept | DexEventKind::Settlement | DexEventKind::Deletion => {
+            check_event_reference(&mut violations, event);
+            None
+        }
+        DexEventKind::Unknown(_) => {
+            violations.push("event kind is not used by the DEX".to_string());
+            None
+        }
+    };
+
+    EventInspection {
+        event_id: event.id,
+        author: event.pubkey,
+        kind,
+        id_valid,
+        signature_valid,
+        decoded,
+        violations,
+    }
+}
+
+impl EventInspection {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for EventInspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let validity = |valid: bool| if valid { "valid" } else { "INVALID" };
+
+        writeln!(f, "Event '{}'", self.event_id)?;
+        writeln!(f, "Kind: {}", self.kind)?;
+        writeln!(f, "Author: {}", self.author)?;
+        writeln!(f, "Id: {}", validity(self.id_valid))?;
+        writeln!(f, "Signature: {}", validity(self.signature_valid))?;
+        if let Some(decoded) = &self.decoded {
+            writeln!(f, "Decoded: {decoded:#?}")?;
+        }
+        if self.violations.is_empty() {
+            write!(f, "Violations: none")
+        } else {
+            write!(f, "Violations:")?;
+            for violation in &self.violations {
+                write!(f, "\n  - {violation}")?;
+            }
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use crate::batch::BatchPlacementReport;
+use crate::error::{NostrRelayError, SourceError};
+use crate::oracle::AttestationQuery;
+use crate::relay_processor::{OrderPlaceEventTags, RelayProcessor};
+use crate::types::MAKER_EXPIRATION_TIME;
+
+use std::path::Path;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use nostr::{EventId, Timestamp};
+use serde::Deserialize;
+
+const DEFAULT_REFRESH_MARGIN_SECS: u64 = 15;
+
+/// Order kept alive by the keeper.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct OrderTemplate {
+    pub asset_to_sell: String,
+    pub asset_to_buy: String,
+    pub price: u64,
+    pub amount: Option<u64>,
+    pub compiler_name: String,
+    pub compiler_build_hash: String,
+    /// Take the price from the keeper price source on every refresh, `price` is used as fallback
+    #[serde(default)]
+    pub reprice: bool,
+}
+
+/// Orders kept by [`MakerKeeper`].
+///
+/// Loaded from TOML:
+/// ```toml
+/// refresh_margin_secs = 15
+/// publish_before_cancel = false
+///
+/// [[orders]]
+/// asset_to_sell = "<asset_id>"
+/// asset_to_buy = "<asset_id>"
+/// price = 1000
+/// amount = 50
+/// reprice = true
+/// compiler_name = "simplicity-v1.2.3"
+/// compiler_build_hash = "<deterministic_build_hash>"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct KeeperSpec {
+    /// Orders are republished this amount of seconds before they expire
+    #[serde(default = "default_refresh_margin_secs")]
+    pub refresh_margin_secs: u64,
+    /// Publish fresh orders before cancelling the previous ones, so the book never stays empty.
+    ///
+    /// Both generations are live until the cancellation is processed, so the same liquidity
+    /// may be taken twice. By default previous orders are cancelled first.
+    #[serde(default)]
+    pub publish_before_cancel: bool,
+    pub orders: Vec<OrderTemplate>,
+}
+
+/// Source of order prices for templates with `reprice` enabled.
+pub trait PriceSource: Send + Sync {
+    fn price<'a>(
+        &'a self,
+        processor: &'a RelayProcessor,
+        template: &'a OrderTemplate,
+    ) -> BoxFuture<'a, crate::error::Result<u64>>;
+}
+
+/// Prices orders by the oracle median of `asset_to_sell` in `asset_to_buy`, adjusted by the spread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OraclePriceSource {
+    pub tolerance: Duration,
+    /// Markup over the oracle price in basis points
+    pub spread_bps: u32,
+}
+
+/// Republishes order templates before they expire and cancels them on shutdown.
+pub struct MakerKeeper<'a> {
+    processor: &'a RelayProcessor,
+    spec: KeeperSpec,
+    price_source: Option<Box<dyn PriceSource + 'a>>,
+    live_orders: Vec<EventId>,
+}
+
+fn default_refresh_margin_secs() -> u64 {
+    DEFAULT_REFRESH_MARGIN_SECS
+}
+
+impl KeeperSpec {
+    pub fn from_toml_str(config: &str) -> crate::error::Result<Self> {
+        toml::from_str(config).map_err(|err| NostrRelayError::FailedToLoadKeeperSpec {
+            source_name: "toml".to_string(),
+            source: err.into(),
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let to_error = |source: SourceError| NostrRelayError::FailedToLoadKeeperSpec {
+            source_name: path.display().to_string(),
+            source,
+        };
+
+        let config = std::fs::read_to_string(path).map_err(|err| to_error(err.into()))?;
+        toml::from_str(&config).map_err(|err| to_error(err.into()))
+    }
+
+    /// Delay between refreshes, orders live for [`MAKER_EXPIRATION_TIME`] seconds.
+    pub fn refresh_interval(&self) -> Duration {
+        Duration::from_secs(MAKER_EXPIRATION_TIME.saturating_sub(self.refresh_margin_secs).max(1))
+    }
+}
+
+impl OrderTemplate {
+    pub fn to_order_tags(&self, price: u64) -> OrderPlaceEventTags {
+        OrderPlaceEventTags {
+            asset_to_sell: self.asset_to_sell.clone(),
+            asset_to_buy: self.asset_to_buy.clone(),
+            price,
+            amount: self.amount,
+            compiler_name: self.compiler_name.clone(),
+            compiler_build_hash: self.compiler_build_hash.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl OraclePriceSource {
+    pub fn apply_spread(&self, price: u64) -> u64 {
+        let price = u128::from(price) * (10_000 + u128::from(self.spread_bps)) / 10_000;
+        u64::try_from(price).unwrap_or(u64::MAX)
+    }
+}
+
+impl PriceSource for OraclePriceSource {
+    fn price<'a>(
+        &'a self,
+        processor: &'a RelayProcessor,
+        template: &'a OrderTemplate,
+    ) -> BoxFuture<'a, crate::error::Result<u64>> {
+        Box::pin(async move {
+            let query = AttestationQuery {
+                base_asset: template.asset_to_sell.clone(),
+                quote_asset: template.asset_to_buy.clone(),
+                timestamp: Timestamp::now(),
+                tolerance: self.tolerance,
+            };
+            let aggregated = processor.get_oracle_price(&query).await?;
+
+            Ok(self.apply_spread(aggregated.price))
+        })
+    }
+}
+
+impl<'a> MakerKeeper<'a> {
+    pub fn new(processor: &'a RelayProcessor, spec: KeeperSpec) -> Self {
+        Self {
+            processor,
+            spec,
+            price_source: None,
+            live_orders: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_price_source(mut self, price_source: impl PriceSource + 'a) -> Self {
+        self.price_source = Some(Box::new(price_source));
+        self
+    }
+
+    pub fn live_orders(&self) -> &[EventId] {
+        &self.live_orders
+    }
+
+    /// Replaces live orders by fresh ones, see [`KeeperSpec::publish_before_cancel`] for the order of steps.
+    pub async fn refresh(&mut self) -> crate::error::Result<BatchPlacementReport> {
+        let mut orders = Vec::with_capacity(self.spec.orders.len());
+        for template in &self.spec.orders {
+            let price = match &self.price_source {
+                Some(price_source) if template.reprice => match price_source.price(self.processor, template).await {
+                    Ok(price) => price,
+                    Err(err) => {
+                        tracing::warn!(error = %err, "Failed to reprice order, using template price");
+                        template.price
+                    }
+                },
+                _ => template.price,
+            };
+            orders.push(template.to_order_tags(price));
+        }
+
+        if !self.spec.publish_before_cancel {
+            let previous = std::mem::take(&mut self.live_orders);
+            self.cancel_previous(previous).await;
+        }
+        let report = self.processor.place_orders(orders).await?;
+        let previous = std::mem::replace(
+            &mut self.live_orders,
+            report.placed.iter().map(|(_, event_id)| *event_id).collect(),
+        );
+        self.cancel_previous(previous).await;
+
+        Ok(report)
+    }
+
+    async fn cancel_previous(&self, previous: Vec<EventId>) {
+        if !previous.is_empty()
+            && let Err(err) = self.processor.cancel_orders(previous).await
+        {
+            tracing::warn!(error = %err, "Failed to cancel refreshed orders, they expire on their own");
+        }
+    }
+
+    /// Keeps orders alive until `shutdown` resolves, live orders are cancelled on every exit path.
+    pub async fn run(&mut self, shutdown: impl Future<Output = ()>) -> crate::error::Result<()> {
+        tokio::pin!(shutdown);
+        loop {
+            let report = match self.refresh().await {
+                Ok(report) => report,
+                Err(err) => {
+                    if let Err(shutdown_err) = self.shutdown().await {
+                        tracing::warn!(error = %shutdown_err, "Failed to cancel live orders, they expire on their own");
+                    }
+                    return Err(err);
+                }
+            };
+            tracing::info!(
+                placed = report.placed.len(),
+                failed = report.failed.len(),
+                "Refreshed maker orders"
+            );
+
+            tokio::select! {
+                () = tokio::time::sleep(self.spec.refresh_interval()) => {}
+                () = &mut shutdown => break,
+            }
+        }
+
+        self.shutdown().await.map(|_| ())
+    }
+
+    /// Cancels live orders, returns the deletion request if any order was live.
+    pub async fn shutdown(&mut self) -> crate::error::Result<Option<EventId>> {
+        if self.live_orders.is_empty() {
+            return Ok(None);
+        }
+
+        let live_orders = std::mem::take(&mut self.live_orders);
+        self.processor.cancel_orders(live_orders).await.map(Some)
+    }
+}
@@ -1,5 +1,27 @@
+pub mod archive;
+pub mod batch;
+pub mod compiler_provenance;
+pub mod contract_verification;
 pub mod error;
 pub mod handlers;
+pub mod inspection;
+pub mod keeper;
+pub mod maker_registry;
+pub mod metrics;
+pub mod offline;
+pub mod option_terms;
+pub mod oracle;
+pub mod pagination;
+pub mod pow;
+pub mod query;
 pub mod relay_client;
+pub mod relay_health;
+pub mod relay_info;
 pub mod relay_processor;
+pub mod reputation;
+pub mod retry;
+pub mod rfq;
+pub mod settlement;
+pub mod swap;
+pub(crate) mod tags;
 pub mod types;
@@ -0,0 +1,131 @@
+use crate::error::{NostrRelayError, SourceError};
+
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use nostr::PublicKey;
+use serde_json::json;
+
+const IS_REGISTERED_RPC_METHOD: &str = "maker_registry_is_registered";
+
+/// Source of truth about makers registered in the Maker Identity Registry.
+pub trait MakerRegistry: Send + Sync {
+    fn is_registered<'a>(&'a self, maker: &'a PublicKey) -> BoxFuture<'a, crate::error::Result<bool>>;
+}
+
+/// Registry backed by a local list of maker public keys (bech32 or hex, one per line).
+///
+/// Blank lines and lines starting with `#` are ignored, malformed lines are skipped with a warning.
+#[derive(Debug, Default, Clone)]
+pub struct FileMakerRegistry {
+    makers: HashSet<PublicKey>,
+}
+
+impl FileMakerRegistry {
+    pub fn new(makers: impl IntoIterator<Item = PublicKey>) -> Self {
+        Self {
+            makers: makers.into_iter().collect(),
+        }
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let to_error = |err: std::io::Error| NostrRelayError::FailedToLoadMakerRegistry {
+            source_name: path.display().to_string(),
+            source: err.into(),
+        };
+
+        let file = std::fs::File::open(path).map_err(to_error)?;
+        let mut makers = HashSet::new();
+        for (line_number, line) in std::io::BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(to_error)?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match PublicKey::from_str(line) {
+                Ok(maker) => {
+                    makers.insert(maker);
+                }
+                Err(err) => tracing::warn!(
+                    "Skipping maker '{line}' ({}:{}), err: {err}",
+                    path.display(),
+                    line_number + 1
+                ),
+            }
+        }
+
+        Ok(Self { makers })
+    }
+}
+
+impl MakerRegistry for FileMakerRegistry {
+    fn is_registered<'a>(&'a self, maker: &'a PublicKey) -> BoxFuture<'a, crate::error::Result<bool>> {
+        Box::pin(async move { Ok(self.makers.contains(maker)) })
+    }
+}
+
+/// Registry queried over JSON-RPC, e.g. a node indexing the on-chain registry or a local stub.
+///
+/// Sends `maker_registry_is_registered` with hex-encoded maker key and expects boolean result.
+#[derive(Debug, Clone)]
+pub struct RpcMakerRegistry {
+    http_client: reqwest::Client,
+    endpoint: String,
+    timeout: Duration,
+}
+
+impl RpcMakerRegistry {
+    pub fn new(endpoint: impl Into<String>, timeout: Duration) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            endpoint: endpoint.into(),
+            timeout,
+        }
+    }
+
+    async fn request_registration(&self, maker: &PublicKey) -> crate::error::Result<bool> {
+        let to_error = |source: SourceError| NostrRelayError::FailedToLoadMakerRegistry {
+            source_name: self.endpoint.clone(),
+            source,
+        };
+
+        let response = self
+            .http_client
+            .post(&self.endpoint)
+            .timeout(self.timeout)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": IS_REGISTERED_RPC_METHOD,
+                "params": [maker.to_hex()],
+            }))
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|err| to_error(err.into()))?
+            .json::<serde_json::Value>()
+            .await
+            .map_err(|err| to_error(err.into()))?;
+
+        if let Some(err) = response.get("error").filter(|err| !err.is_null()) {
+            return Err(to_error(SourceError::Rpc(err.clone())));
+        }
+
+        response
+            .get("result")
+            .and_then(serde_json::Value::as_bool)
+            .ok_or_else(|| to_error(SourceError::UnexpectedResponse(response.clone())))
+    }
+}
+
+impl MakerRegistry for RpcMakerRegistry {
+    fn is_registered<'a>(&'a self, maker: &'a PublicKey) -> BoxFuture<'a, crate::error::Result<bool>> {
+        Box::pin(self.request_registration(maker))
+    }
+}
@@ -0,0 +1,49 @@
+use crate::tags::tag_value;
+
+use std::str::FromStr;
+
+use elements::AssetId;
+use global_utils::metrics::{Counter, DEFAULT_LATENCY_BUCKETS, Histogram};
+use nostr::Event;
+
+const UNKNOWN_ASSET: &str = "unknown";
+
+pub static EVENTS_PUBLISHED: Counter = Counter::new("dex_relay_events_published_total", "Events accepted by the relay");
+pub static EVENTS_REJECTED: Counter = Counter::new(
+    "dex_relay_events_rejected_total",
+    "Events rejected by the relay or not delivered to it after all publish attempts",
+);
+pub static PUBLISH_RETRIES: Counter = Counter::new(
+    "dex_relay_publish_retries_total",
+    "Publish attempts repeated after a transient relay failure",
+);
+pub static FETCH_DURATION: Histogram = Histogram::new(
+    "dex_relay_fetch_duration_seconds",
+    "Duration of successful fetches from the relay",
+    DEFAULT_LATENCY_BUCKETS,
+);
+pub static RELAY_RECONNECTS: Counter = Counter::new(
+    "dex_relay_reconnects_total",
+    "Connections re-established after the relay connection was lost",
+);
+pub static ORDERS_SEEN: Counter = Counter::new(
+    "dex_orders_seen_total",
+    "Maker orders fetched from relays per asset pair",
+);
+pub static ORDERS_PLACED: Counter = Counter::new("dex_orders_placed_total", "Maker orders published per asset pair");
+
+/// Labels of the asset pair of the order, malformed orders are counted under `unknown` assets.
+///
+/// Tags come from untrusted relay events, so only valid asset ids become label values.
+pub(crate) fn order_pair_labels(event: &Event) -> [(&'static str, &str); 2] {
+    [
+        ("asset_to_sell", asset_label(event, "asset_to_sell")),
+        ("asset_to_buy", asset_label(event, "asset_to_buy")),
+    ]
+}
+
+fn asset_label<'a>(event: &'a Event, tag: &str) -> &'a str {
+    tag_value(event, tag, 1)
+        .filter(|asset| AssetId::from_str(asset).is_ok())
+        .unwrap_or(UNKNOWN_ASSET)
+}
@@ -0,0 +1,72 @@
+use crate::error::NostrRelayError;
+use crate::types::{CustomKind, MakerOrderKind, TakerOrderKind};
+
+use nostr::{Event, EventId, JsonUtil, Keys, UnsignedEvent};
+
+fn offline_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> NostrRelayError {
+    NostrRelayError::InvalidOfflineEvent { source: err.into() }
+}
+
+fn check_order_kind(kind: nostr::Kind) -> crate::error::Result<()> {
+    if kind != MakerOrderKind::get_kind() && kind != TakerOrderKind::get_kind() {
+        return Err(offline_error(format!(
+            "kind {} is neither maker order nor taker reply",
+            kind.as_u16()
+        )));
+    }
+    Ok(())
+}
+
+/// Parses unsigned order event exported for offline signing.
+///
+/// Exported id must match the event content, so the signer sees exactly what is going to be published.
+pub fn parse_unsigned_event(json: &str) -> crate::error::Result<UnsignedEvent> {
+    let mut unsigned = UnsignedEvent::from_json(json).map_err(offline_error)?;
+    check_order_kind(unsigned.kind)?;
+
+    let computed_id = EventId::new(
+        &unsigned.pubkey,
+        &unsigned.created_at,
+        &unsigned.kind,
+        &unsigned.tags,
+        &unsigned.content,
+    );
+    match unsigned.id {
+        Some(id) if id != computed_id => {
+            return Err(offline_error(format!("id {id} does not match event content")));
+        }
+        Some(_) => {}
+        None => unsigned.ensure_id(),
+    }
+
+    Ok(unsigned)
+}
+
+/// Signs exported event with the key which was declared as its author on export.
+pub fn sign_unsigned_event(unsigned: UnsignedEvent, keys: &Keys) -> crate::error::Result<Event> {
+    if unsigned.pubkey != keys.public_key {
+        return Err(offline_error(format!(
+            "event is exported for {}, but the key belongs to {}",
+            unsigned.pubkey, keys.public_key
+        )));
+    }
+
+    unsigned.sign_with_keys(keys).map_err(offline_error)
+}
+
+/// Checks signature and kind of the pre-signed order event before it is published.
+pub fn verify_signed_event(event: &Event) -> crate::error::Result<()> {
+    event.verify().map_err(offline_error)?;
+    check_order_kind(event.kind)?;
+    if event.is_expired() {
+        return Err(NostrRelayError::ExpiredEvent(event.id));
+    }
+    Ok(())
+}
+
+/// Parses and verifies pre-signed order event.
+pub fn parse_signed_event(json: &str) -> crate::error::Result<Event> {
+    let event = Event::from_json(json).map_err(offline_error)?;
+    verify_signed_event(&event)?;
+    Ok(event)
+}
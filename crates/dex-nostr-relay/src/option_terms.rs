@@ -0,0 +1,256 @@
+use crate::error::NostrRelayError;
+use crate::tags::{required_tag_value, tag_value};
+
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+use nostr::{Event, Tag, TagKind, Timestamp};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+/// Exercise style, European options are exercisable only at settlement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionStyle {
+    European,
+    American,
+}
+
+/// Settlement point of the option, either Liquid block height or unix timestamp.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Settlement {
+    Height(u32),
+    Time(u64),
+}
+
+/// Option contract parameters carried by the order.
+///
+/// Encoded in tags:
+/// ```json
+/// [
+///   ["option_type", "call"],
+///   ["option_style", "european"],
+///   ["strike_price", "1000000"],
+///   ["settlement", "time", "1735689600"],
+///   ["collateral_asset", "<liquid_asset_id>"],
+///   ["settlement_asset", "<liquid_asset_id>"]
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionTerms {
+    pub option_type: OptionType,
+    pub style: OptionStyle,
+    /// Amount of `settlement_asset` per contract
+    pub strike_price: u64,
+    pub settlement: Settlement,
+    pub collateral_asset: String,
+    pub settlement_asset: String,
+}
+
+/// Criteria for option orders, every `None` field matches any value.
+#[derive(Debug, Default, Clone)]
+pub struct OptionTermsFilter {
+    pub option_type: Option<OptionType>,
+    pub style: Option<OptionStyle>,
+    pub min_strike_price: Option<u64>,
+    pub max_strike_price: Option<u64>,
+    /// Keep options settling not later than the given timestamp, height-settled options are dropped
+    pub settles_before: Option<u64>,
+    pub collateral_asset: Option<String>,
+    pub settlement_asset: Option<String>,
+}
+
+fn invalid_tag(tag: &str, err_msg: impl Into<String>) -> NostrRelayError {
+    NostrRelayError::InvalidOrderTag {
+        tag: tag.to_string(),
+        err_msg: err_msg.into(),
+    }
+}
+
+impl fmt::Display for OptionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionType::Call => write!(f, "call"),
+            OptionType::Put => write!(f, "put"),
+        }
+    }
+}
+
+impl FromStr for OptionType {
+    type Err = NostrRelayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "call" => Ok(OptionType::Call),
+            "put" => Ok(OptionType::Put),
+            _ => Err(invalid_tag("option_type", format!("'{s}' is not 'call' or 'put'"))),
+        }
+    }
+}
+
+impl fmt::Display for OptionStyle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OptionStyle::European => write!(f, "european"),
+            OptionStyle::American => write!(f, "american"),
+        }
+    }
+}
+
+impl FromStr for OptionStyle {
+    type Err = NostrRelayError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "european" => Ok(OptionStyle::European),
+            "american" => Ok(OptionStyle::American),
+            _ => Err(invalid_tag(
+                "option_style",
+                format!("'{s}' is not 'european' or 'american'"),
+            )),
+        }
+    }
+}
+
+fn validate_asset(tag: &str, asset: &str) -> crate::error::Result<()> {
+    if asset.len() != 64 || !asset.chars().all(|x| x.is_ascii_hexdigit()) {
+        return Err(invalid_tag(tag, format!("'{asset}' is not a valid asset id")));
+    }
+    Ok(())
+}
+
+impl OptionTerms {
+    /// Checks terms consistency, does not depend on the current time.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.strike_price == 0 {
+            return Err(invalid_tag("strike_price", "strike price must be positive"));
+        }
+        if matches!(self.settlement, Settlement::Height(0) | Settlement::Time(0)) {
+            return Err(invalid_tag("settlement", "settlement must be positive"));
+        }
+        validate_asset("collateral_asset", &self.collateral_asset)?;
+        validate_asset("settlement_asset", &self.settlement_asset)?;
+        if self.collateral_asset.eq_ignore_ascii_case(&self.settlement_asset) {
+            return Err(invalid_tag(
+                "settlement_asset",
+                "settlement asset must differ from collateral asset",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Validates terms of the option being placed, time-based settlement must be in the future.
+    pub fn validate_for_placement(&self, now: Timestamp) -> crate::error::Result<()> {
+        self.validate()?;
+        if let Settlement::Time(time) = self.settlement
+            && time <= now.as_u64()
+        {
+            return Err(invalid_tag(
+                "settlement",
+                format!("settlement time {time} is in the past"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Parses option terms of the order, `None` if the order is not an option.
+    pub fn from_event(event: &Event) -> crate::error::Result<Option<Self>> {
+        let Some(option_type) = tag_value(event, "option_type", 1) else {
+            return Ok(None);
+        };
+
+        let strike_price = required_tag_value(event, "strike_price", 1)?;
+        let strike_price = strike_price
+            .parse::<u64>()
+            .map_err(|err| invalid_tag("strike_price", format!("'{strike_price}' is not a valid price, {err}")))?;
+
+        let settlement_value = required_tag_value(event, "settlement", 2)?;
+        let settlement = match required_tag_value(event, "settlement", 1)? {
+            "height" => settlement_value.parse::<u32>().map(Settlement::Height),
+            "time" => settlement_value.parse::<u64>().map(Settlement::Time),
+            other => {
+                return Err(invalid_tag(
+                    "settlement",
+                    format!("'{other}' is not 'height' or 'time'"),
+                ));
+            }
+        }
+        .map_err(|err| invalid_tag("settlement", format!("'{settlement_value}' is not valid, {err}")))?;
+
+        let terms = Self {
+            option_type: option_type.parse()?,
+            style: required_tag_value(event, "option_style", 1)?.parse()?,
+            strike_price,
+            settlement,
+            collateral_asset: required_tag_value(event, "collateral_asset", 1)?.to_string(),
+            settlement_asset: required_tag_value(event, "settlement_asset", 1)?.to_string(),
+        };
+        terms.validate()?;
+
+        Ok(Some(terms))
+    }
+
+    pub fn to_tags(&self) -> Vec<Tag> {
+        let settlement = match self.settlement {
+            Settlement::Height(height) => ["height".to_string(), height.to_string()],
+            Settlement::Time(time) => ["time".to_string(), time.to_string()],
+        };
+
+        vec![
+            Tag::custom(
+                TagKind::Custom(Cow::from("option_type")),
+                [self.option_type.to_string()],
+            ),
+            Tag::custom(TagKind::Custom(Cow::from("option_style")), [self.style.to_string()]),
+            Tag::custom(
+                TagKind::Custom(Cow::from("strike_price")),
+                [self.strike_price.to_string()],
+            ),
+            Tag::custom(TagKind::Custom(Cow::from("settlement")), settlement),
+            Tag::custom(
+                TagKind::Custom(Cow::from("collateral_asset")),
+                [self.collateral_asset.clone()],
+            ),
+            Tag::custom(
+                TagKind::Custom(Cow::from("settlement_asset")),
+                [self.settlement_asset.clone()],
+            ),
+        ]
+    }
+}
+
+impl OptionTermsFilter {
+    /// Returns `true` if any criterion is set, i.e. the filter does not match every option.
+    pub fn has_criteria(&self) -> bool {
+        self.option_type.is_some()
+            || self.style.is_some()
+            || self.min_strike_price.is_some()
+            || self.max_strike_price.is_some()
+            || self.settles_before.is_some()
+            || self.collateral_asset.is_some()
+            || self.settlement_asset.is_some()
+    }
+
+    pub fn matches(&self, terms: &OptionTerms) -> bool {
+        self.option_type.is_none_or(|x| x == terms.option_type)
+            && self.style.is_none_or(|x| x == terms.style)
+            && self.min_strike_price.is_none_or(|x| terms.strike_price >= x)
+            && self.max_strike_price.is_none_or(|x| terms.strike_price <= x)
+            && self.settles_before.is_none_or(|x| match terms.settlement {
+                Settlement::Time(time) => time <= x,
+                Settlement::Height(_) => false,
+            })
+            && self
+                .collateral_asset
+                .as_deref()
+                .is_none_or(|x| x.eq_ignore_ascii_case(&terms.collateral_asset))
+            && self
+                .settlement_asset
+                .as_deref()
+                .is_none_or(|x| x.eq_ignore_ascii_case(&terms.settlement_asset))
+    }
+}
@@ -0,0 +1,203 @@
+use crate::error::NostrRelayError;
+use crate::tags::required_tag_value;
+use crate::types::{CustomKind, OracleAttestationKind};
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+use nostr::{Event, EventId, PublicKey, Tag, TagKind, Timestamp};
+
+/// Price of `base_asset` in `quote_asset` units attested by the oracle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceAttestationTags {
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub price: u64,
+    /// Moment the price was observed at, may differ from event creation time
+    pub timestamp: Timestamp,
+}
+
+/// Attestation parsed from a verified oracle event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PriceAttestation {
+    pub event_id: EventId,
+    pub oracle: PublicKey,
+    pub tags: PriceAttestationTags,
+}
+
+/// Attestations for the asset pair around the given moment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttestationQuery {
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub timestamp: Timestamp,
+    /// Maximum distance between the attested and the requested timestamp
+    pub tolerance: Duration,
+}
+
+/// Median price agreed by at least `threshold` oracles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatedPrice {
+    pub price: u64,
+    pub oracles: BTreeSet<PublicKey>,
+    pub attestations: Vec<EventId>,
+}
+
+/// Oracles trusted by the client together with the amount of attestations required for a price.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OracleSet {
+    oracles: BTreeSet<PublicKey>,
+    threshold: usize,
+}
+
+impl PriceAttestationTags {
+    pub fn to_tags(&self) -> Vec<Tag> {
+        vec![
+            Tag::custom(TagKind::Custom(Cow::from("base_asset")), [self.base_asset.clone()]),
+            Tag::custom(TagKind::Custom(Cow::from("quote_asset")), [self.quote_asset.clone()]),
+            Tag::custom(TagKind::Custom(Cow::from("price")), [self.price.to_string()]),
+            Tag::custom(
+                TagKind::Custom(Cow::from("attested_at")),
+                [self.timestamp.as_u64().to_string()],
+            ),
+        ]
+    }
+}
+
+impl TryFrom<&Event> for PriceAttestation {
+    type Error = NostrRelayError;
+
+    /// Parses oracle event, checking its kind and signature.
+    fn try_from(event: &Event) -> crate::error::Result<Self> {
+        if event.kind != OracleAttestationKind::get_kind() {
+            return Err(NostrRelayError::UnexpectedEventKind {
+                expected: OracleAttestationKind::get_u16(),
+                got: event.kind.as_u16(),
+            });
+        }
+        let to_error = |err: Box<dyn std::error::Error + Send + Sync>| NostrRelayError::InvalidPriceAttestation {
+            event_id: event.id,
+            source: err,
+        };
+        event.verify().map_err(|err| to_error(err.into()))?;
+
+        let parse_u64 = |tag: &str| -> crate::error::Result<u64> {
+            let value = required_tag_value(event, tag, 1)?;
+            value
+                .parse::<u64>()
+                .map_err(|err| to_error(format!("'{value}' is not a valid {tag}, {err}").into()))
+        };
+
+        Ok(Self {
+            event_id: event.id,
+            oracle: event.pubkey,
+            tags: PriceAttestationTags {
+                base_asset: required_tag_value(event, "base_asset", 1)?.to_string(),
+                quote_asset: required_tag_value(event, "quote_asset", 1)?.to_string(),
+                price: parse_u64("price")?,
+                timestamp: Timestamp::from(parse_u64("attested_at")?),
+            },
+        })
+    }
+}
+
+impl AttestationQuery {
+    pub fn matches(&self, attestation: &PriceAttestation) -> bool {
+        attestation.tags.base_asset.eq_ignore_ascii_case(&self.base_asset)
+            && attestation.tags.quote_asset.eq_ignore_ascii_case(&self.quote_asset)
+            && attestation.tags.timestamp.as_u64().abs_diff(self.timestamp.as_u64()) <= self.tolerance.as_secs()
+    }
+}
+
+impl OracleSet {
+    pub fn new(oracles: impl IntoIterator<Item = PublicKey>, threshold: usize) -> Self {
+        Self {
+            oracles: oracles.into_iter().collect(),
+            threshold,
+        }
+    }
+
+    /// Loads oracle public keys (bech32 or hex, one per line), blank lines and `#` comments are ignored.
+    ///
+    /// Malformed keys are skipped with a warning.
+    pub fn from_file(path: impl AsRef<Path>, threshold: usize) -> crate::error::Result<Self> {
+        let path = path.as_ref();
+        let to_error = |err: std::io::Error| NostrRelayError::FailedToLoadOracleSet {
+            source_name: path.display().to_string(),
+            source: err.into(),
+        };
+
+        let file = std::fs::File::open(path).map_err(to_error)?;
+        let mut oracles = BTreeSet::new();
+        for (line_number, line) in std::io::BufReader::new(file).lines().enumerate() {
+            let line = line.map_err(to_error)?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match PublicKey::from_str(line) {
+                Ok(oracle) => {
+                    oracles.insert(oracle);
+                }
+                Err(err) => tracing::warn!(
+                    "Skipping oracle '{line}' ({}:{}), err: {err}",
+                    path.display(),
+                    line_number + 1
+                ),
+            }
+        }
+
+        Ok(Self { oracles, threshold })
+    }
+
+    pub fn oracles(&self) -> &BTreeSet<PublicKey> {
+        &self.oracles
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn is_trusted(&self, oracle: &PublicKey) -> bool {
+        self.oracles.contains(oracle)
+    }
+
+    /// Aggregates attestations of trusted oracles into a median price at the given moment.
+    ///
+    /// Only the attestation closest to `at` is counted for every oracle, the earlier one on a tie,
+    /// so a single oracle cannot reach the threshold alone. For an even amount of oracles the
+    /// lower median is used.
+    pub fn aggregate(&self, attestations: &[PriceAttestation], at: Timestamp) -> crate::error::Result<AggregatedPrice> {
+        let distance = |x: &PriceAttestation| (x.tags.timestamp.as_u64().abs_diff(at.as_u64()), x.tags.timestamp);
+
+        let mut closest: BTreeMap<PublicKey, &PriceAttestation> = BTreeMap::new();
+        for attestation in attestations.iter().filter(|x| self.is_trusted(&x.oracle)) {
+            let entry = closest.entry(attestation.oracle).or_insert(attestation);
+            if distance(attestation) < distance(entry) {
+                *entry = attestation;
+            }
+        }
+
+        let required = self.threshold.max(1);
+        if closest.len() < required {
+            return Err(NostrRelayError::NotEnoughAttestations {
+                required,
+                available: closest.len(),
+            });
+        }
+
+        let mut prices = closest.values().map(|x| x.tags.price).collect::<Vec<u64>>();
+        prices.sort_unstable();
+
+        Ok(AggregatedPrice {
+            price: prices[(prices.len() - 1) / 2],
+            oracles: closest.keys().copied().collect(),
+            attestations: closest.values().map(|x| x.event_id).collect(),
+        })
+    }
+}
@@ -0,0 +1,112 @@
+use crate::relay_client::RelayClient;
+
+use std::collections::HashSet;
+
+use futures_util::stream::{self, Stream, TryStreamExt};
+use nostr::{Event, EventId, Filter, Timestamp};
+
+/// Events requested from relays per window, relays may cap it further.
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// Cursor over `until`/`limit` windows of a filter, deduplicating events across relays and windows.
+///
+/// Relays may cap windows at different depths, so every window ends at the newest of the
+/// oldest events returned per relay inclusive. Events sharing that timestamp are not lost
+/// when a relay truncates the window in the middle of them, unless they fill the whole window.
+#[derive(Debug, Clone)]
+pub struct PageWindow {
+    filter: Filter,
+    page_size: usize,
+    until: Option<Timestamp>,
+    seen: HashSet<EventId>,
+    exhausted: bool,
+}
+
+impl PageWindow {
+    /// Windows start at `until` of the filter, or at the newest events if it is not set.
+    pub fn new(filter: Filter, page_size: usize) -> Self {
+        Self {
+            until: filter.until,
+            filter,
+            page_size: page_size.max(1),
+            seen: HashSet::new(),
+            exhausted: false,
+        }
+    }
+
+    /// Filter of the next window, `None` once history is exhausted.
+    pub fn filter(&self) -> Option<Filter> {
+        if self.exhausted {
+            return None;
+        }
+
+        Some(Filter {
+            until: self.until,
+            limit: Some(self.page_size),
+            ..self.filter.clone()
+        })
+    }
+
+    /// Moves the cursor past the window fetched from every relay, returns its events not seen before, newest first.
+    ///
+    /// The cursor strictly moves back with every window, so relays ignoring `until` can't stall the walk.
+    pub fn advance(&mut self, pages: impl IntoIterator<Item = impl IntoIterator<Item = Event>>) -> Vec<Event> {
+        let mut page = Vec::new();
+        let mut oldest = None;
+        for relay_page in pages {
+            let relay_page = relay_page
+                .into_iter()
+                .filter(|x| self.until.is_none_or(|until| x.created_at <= until))
+                .collect::<Vec<Event>>();
+            // Relay may have truncated the window, its history is complete only down to its oldest event
+            if let Some(relay_oldest) = relay_page.iter().map(|x| x.created_at).min() {
+                oldest = oldest.max(Some(relay_oldest));
+            }
+            page.extend(relay_page);
+        }
+        page.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.id.cmp(&b.id)));
+
+        let Some(oldest) = oldest else {
+            self.exhausted = true;
+            return Vec::new();
+        };
+
+        // Older events may miss ones from relays which truncated the window earlier, they are fetched again later
+        let fresh = page
+            .into_iter()
+            .take_while(|x| x.created_at >= oldest)
+            .filter(|x| self.seen.insert(x.id))
+            .collect::<Vec<Event>>();
+        let step_past_oldest = fresh.is_empty() || self.until.is_some_and(|until| oldest >= until);
+        if step_past_oldest {
+            // Window holds only events at the oldest timestamp of the previous one, step past it
+            match oldest.as_u64().checked_sub(1) {
+                Some(until) => self.until = Some(Timestamp::from_secs(until)),
+                None => self.exhausted = true,
+            }
+        } else {
+            self.until = Some(oldest);
+        }
+
+        fresh
+    }
+}
+
+/// Streams events matching the filter window by window until history is exhausted.
+pub(crate) fn paginate<'a>(
+    client: &'a RelayClient,
+    filter: Filter,
+    page_size: usize,
+) -> impl Stream<Item = crate::error::Result<Event>> + 'a {
+    stream::try_unfold(PageWindow::new(filter, page_size), move |mut window| async move {
+        while let Some(filter) = window.filter() {
+            let fresh = window.advance(client.req_per_relay_and_wait(filter).await?.into_values());
+            if !fresh.is_empty() {
+                return crate::error::Result::Ok(Some((fresh, window)));
+            }
+        }
+        Ok(None)
+    })
+    .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+    .try_flatten()
+}
@@ -0,0 +1,44 @@
+use crate::error::NostrRelayError;
+
+use nostr::nips::nip13::get_leading_zero_bits;
+use nostr::{Event, EventBuilder, PublicKey, TagStandard, UnsignedEvent};
+
+/// Builds unsigned event, mining NIP-13 proof-of-work when difficulty is non-zero.
+///
+/// Mining is CPU bound, so it is executed on the blocking thread pool.
+pub async fn build_event(
+    builder: EventBuilder,
+    public_key: PublicKey,
+    difficulty: u8,
+) -> crate::error::Result<UnsignedEvent> {
+    if difficulty == 0 {
+        return Ok(builder.build(public_key));
+    }
+
+    tracing::debug!(difficulty, "Mining proof-of-work for event");
+
+    tokio::task::spawn_blocking(move || builder.pow(difficulty).build(public_key))
+        .await
+        .map_err(|source| NostrRelayError::PowMiningFailed { source })
+}
+
+/// Returns difficulty committed in the `nonce` tag of the event, if any.
+pub fn committed_difficulty(event: &Event) -> Option<u8> {
+    event.tags.iter().find_map(|tag| match tag.as_standardized() {
+        Some(TagStandard::POW { difficulty, .. }) => Some(*difficulty),
+        _ => None,
+    })
+}
+
+/// Checks that event id has at least `min_difficulty` leading zero bits and commits to such target.
+///
+/// Requiring committed target prevents counting events which got lucky ids without doing the work.
+pub fn meets_difficulty(event: &Event, min_difficulty: u8) -> bool {
+    if min_difficulty == 0 {
+        return true;
+    }
+
+    let committed = committed_difficulty(event).unwrap_or_default();
+
+    committed >= min_difficulty && get_leading_zero_bits(event.id.as_bytes()) >= min_difficulty
+}
@@ -0,0 +1,68 @@
+use crate::pagination::{self, DEFAULT_PAGE_SIZE};
+use crate::relay_client::RelayClient;
+
+use std::collections::BTreeSet;
+use std::pin::pin;
+
+use futures_util::future;
+use futures_util::stream::{Stream, StreamExt, TryStreamExt};
+use nostr::{Event, Filter, PublicKey, Timestamp};
+use nostr_sdk::prelude::Events;
+
+/// Options shared by read queries: time window, authors, amount of events and expiration handling.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct QueryOptions {
+    pub since: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+    pub authors: Option<BTreeSet<PublicKey>>,
+    /// Return at most this amount of events, newest first, all matching events if `None`
+    pub limit: Option<usize>,
+    /// Keep events whose NIP-40 expiration has passed, so historical orders can be audited
+    pub include_expired: bool,
+}
+
+impl QueryOptions {
+    /// Narrows the relay filter to the time window and authors, limit is applied client-side.
+    pub fn apply(&self, filter: Filter) -> Filter {
+        Filter {
+            since: self.since.or(filter.since),
+            until: self.until.or(filter.until),
+            authors: self.authors.clone().or(filter.authors),
+            ..filter
+        }
+    }
+
+    /// Whether the event passes expiration check at `now`.
+    pub fn is_visible(&self, event: &Event, now: Timestamp) -> bool {
+        self.include_expired || !event.is_expired_at(&now)
+    }
+}
+
+/// Streams events matching the filter narrowed by the options, newest first.
+///
+/// [`QueryOptions::limit`] is not applied, so callers can bound the stream after their own filtering.
+pub(crate) fn query_stream<'a>(
+    client: &'a RelayClient,
+    filter: Filter,
+    options: &QueryOptions,
+) -> impl Stream<Item = crate::error::Result<Event>> + use<'a> {
+    let options = options.clone();
+    let time_now = Timestamp::now();
+
+    pagination::paginate(client, options.apply(filter), DEFAULT_PAGE_SIZE)
+        .try_filter(move |event| future::ready(options.is_visible(event, time_now)))
+}
+
+/// Collects events matching the filter narrowed by the options, bounded by [`QueryOptions::limit`].
+pub(crate) async fn query_events(
+    client: &RelayClient,
+    filter: Filter,
+    options: &QueryOptions,
+) -> crate::error::Result<Events> {
+    let mut events = Events::default();
+    let mut stream = pin!(query_stream(client, filter, options).take(options.limit.unwrap_or(usize::MAX)));
+    while let Some(event) = stream.try_next().await? {
+        events.insert(event);
+    }
+    Ok(events)
+}
@@ -1,54 +1,108 @@
-use crate::error::NostrRelayError;
+use crate::error::{NostrRelayError, RelayFailure};
+use crate::metrics;
+use crate::relay_health::{RelayHealth, RelayHealthTracker, RelayOperation};
+use crate::relay_info::{UnsupportedExpirationPolicy, fetch_relay_information};
+use crate::retry::{RetryPolicy, retry};
 
-use std::collections::HashMap;
-use std::fmt::Debug;
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Debug};
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 
+use futures_util::future::join_all;
+use nostr::nips::nip11::RelayInformationDocument;
 use nostr::prelude::*;
 use nostr_sdk::pool::Output;
+use nostr_sdk::pool::monitor::{Monitor, MonitorNotification};
+use nostr_sdk::pool::relay::{self, ReqExitPolicy};
 use nostr_sdk::prelude::Events;
-use nostr_sdk::{Client, Relay, SubscribeAutoCloseOptions};
+use nostr_sdk::{Client, Relay, RelayStatus, SubscribeAutoCloseOptions};
 
-use tracing::instrument;
+use tokio::sync::broadcast::error::RecvError;
+
+use tracing::{Instrument, instrument};
+
+/// Relay status notifications buffered for the reconnect counter.
+const MONITOR_CHANNEL_SIZE: usize = 64;
 
 #[derive(Debug)]
 pub struct RelayClient {
     client: Client,
     timeout: Duration,
+    min_healthy_relays: usize,
+    health: RelayHealthTracker,
+    http_client: reqwest::Client,
+    relays_info: RwLock<HashMap<RelayUrl, RelayInformationDocument>>,
+    unsupported_expiration_policy: UnsupportedExpirationPolicy,
+    pow_difficulty: u8,
+    retry_policy: RetryPolicy,
 }
 
 #[derive(Debug)]
 pub struct ClientConfig {
     pub timeout: Duration,
+    /// Minimum amount of healthy relays required before publishing an event
+    pub min_healthy_relays: usize,
+    /// Amount of consecutive failures after which a relay is temporarily skipped
+    pub demote_after_failures: usize,
+    /// Time a demoted relay is skipped before it gets another chance
+    pub demotion_cooldown: Duration,
+    /// Defines how relays without NIP-40 support are treated on publishing expiring events
+    pub unsupported_expiration_policy: UnsupportedExpirationPolicy,
+    /// NIP-13 proof-of-work difficulty mined for published orders and replies, `0` disables mining
+    pub pow_difficulty: u8,
+    /// Retries of publishing per relay and of fetches which no relay answered
+    pub retry_policy: RetryPolicy,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            min_healthy_relays: 1,
+            demote_after_failures: 3,
+            demotion_cooldown: Duration::from_secs(300),
+            unsupported_expiration_policy: UnsupportedExpirationPolicy::default(),
+            pow_difficulty: 0,
+            retry_policy: RetryPolicy::default(),
+        }
+    }
 }
 
 impl RelayClient {
     #[instrument(skip_all, level = "debug", err)]
     pub async fn connect(
-        relay_urls: impl IntoIterator<Item = impl TryIntoUrl>,
+        relay_urls: impl IntoIterator<Item = impl TryIntoUrl<Err: std::error::Error + Send + Sync + 'static> + fmt::Display>,
         keys: Option<impl IntoNostrSigner>,
         client_config: ClientConfig,
     ) -> crate::error::Result<Self> {
         tracing::debug!(client_config = ?client_config, "Connecting to Nostr Relay Client(s)");
 
+        let monitor = Monitor::new(MONITOR_CHANNEL_SIZE);
+        count_reconnects(&monitor);
         let client = match keys {
-            None => Client::default(),
+            None => Client::builder().monitor(monitor).build(),
             Some(keys) => {
-                let client = Client::new(keys);
+                let client = Client::builder().signer(keys).monitor(monitor).build();
                 client.automatic_authentication(true);
                 client
             }
         };
 
         for url in relay_urls {
-            let url = url
-                .try_into_url()
-                .map_err(|err| NostrRelayError::FailedToConvertRelayUrl {
-                    err_msg: format!("{:?}", err),
-                })?;
+            let raw_url = url.to_string();
+            let url = url.try_into_url().map_err(|err| NostrRelayError::InvalidRelayUrl {
+                url: raw_url.clone(),
+                source: Box::new(err),
+            })?;
 
-            client.add_relay(url).await?;
+            client
+                .add_relay(url)
+                .await
+                .map_err(|err| NostrRelayError::FailedToAddRelay {
+                    url: raw_url,
+                    source: err,
+                })?;
         }
 
         client.connect().await;
@@ -56,14 +110,111 @@ impl RelayClient {
         Ok(Self {
             client,
             timeout: client_config.timeout,
+            min_healthy_relays: client_config.min_healthy_relays,
+            health: RelayHealthTracker::new(client_config.demote_after_failures, client_config.demotion_cooldown),
+            http_client: reqwest::Client::new(),
+            relays_info: RwLock::new(HashMap::new()),
+            unsupported_expiration_policy: client_config.unsupported_expiration_policy,
+            pow_difficulty: client_config.pow_difficulty,
+            retry_policy: client_config.retry_policy,
         })
     }
 
+    /// Fetches events from every healthy relay in parallel, tracking outcome and latency per relay.
+    ///
+    /// Fetch is repeated according to the retry policy if no relay answered.
     #[instrument(skip_all, level = "debug", ret)]
     pub async fn req_and_wait(&self, filter: Filter) -> crate::error::Result<Events> {
         tracing::debug!(filter = ?filter, "Requesting events with filter");
 
-        Ok(self.client.fetch_combined_events(filter, self.timeout).await?)
+        retry(&self.retry_policy, RelayOperation::Fetch, || {
+            self.fetch_once(filter.clone())
+        })
+        .await
+    }
+
+    /// Fetches events like [`RelayClient::req_and_wait`], keeping them apart per relay which answered.
+    ///
+    /// Events stored in the local database are not included.
+    #[instrument(skip_all, level = "debug", ret)]
+    pub async fn req_per_relay_and_wait(&self, filter: Filter) -> crate::error::Result<HashMap<RelayUrl, Events>> {
+        tracing::debug!(filter = ?filter, "Requesting events per relay with filter");
+
+        retry(&self.retry_policy, RelayOperation::Fetch, || {
+            self.fetch_per_relay_once(filter.clone())
+        })
+        .await
+    }
+
+    async fn fetch_once(&self, filter: Filter) -> crate::error::Result<Events> {
+        let fetched = self.fetch_per_relay_once(filter.clone()).await?;
+
+        let events = self
+            .client
+            .database()
+            .query(filter)
+            .await
+            .map_err(|err| NostrRelayError::NostrClientFailure(nostr_sdk::client::Error::Database(err)))?;
+
+        Ok(fetched.into_values().fold(events, Events::merge))
+    }
+
+    async fn fetch_per_relay_once(&self, filter: Filter) -> crate::error::Result<HashMap<RelayUrl, Events>> {
+        let relays = self.healthy_relays().await;
+        if relays.is_empty() {
+            return Err(NostrRelayError::NoRelaysConnected {
+                operation: RelayOperation::Fetch,
+            });
+        }
+
+        let fetches = relays.iter().map(|(url, relay)| {
+            let filter = filter.clone();
+            async move {
+                let started_at = Instant::now();
+                let res = relay
+                    .fetch_events(filter, self.timeout, ReqExitPolicy::ExitOnEOSE)
+                    .await;
+                (url, res, started_at.elapsed())
+            }
+        });
+        let results = join_all(fetches).await;
+
+        let mut pages = HashMap::new();
+        let mut timed_out = 0;
+        let mut failures = Vec::new();
+
+        for (url, res, elapsed) in results {
+            match res {
+                Ok(fetched) => {
+                    self.health.record_success(url, RelayOperation::Fetch, Some(elapsed));
+                    metrics::FETCH_DURATION.observe_duration(&[("relay", url.as_str())], elapsed);
+                    pages.insert(url.clone(), fetched);
+                }
+                Err(err) => {
+                    tracing::warn!(url = %url, error = %err, "Failed to fetch events from relay");
+                    self.health.record_failure(url, RelayOperation::Fetch, &err);
+                    if matches!(err, relay::Error::Timeout) {
+                        timed_out += 1;
+                    }
+                    failures.push(RelayFailure {
+                        url: url.clone(),
+                        reason: err.to_string(),
+                    });
+                }
+            }
+        }
+
+        match pages.len() {
+            0 if timed_out == failures.len() => Err(NostrRelayError::Timeout {
+                operation: RelayOperation::Fetch,
+                timeout: self.timeout,
+            }),
+            0 => Err(NostrRelayError::AllRelaysRejected {
+                operation: RelayOperation::Fetch,
+                failures,
+            }),
+            _ => Ok(pages),
+        }
     }
 
     #[instrument(skip_all, level = "debug", ret)]
@@ -72,7 +223,14 @@ impl RelayClient {
             return Err(NostrRelayError::MissingSigner);
         }
 
-        Ok(self.client.signer().await?)
+        self.client
+            .signer()
+            .await
+            .map_err(|err| NostrRelayError::SignerUnavailable { source: err })
+    }
+
+    pub fn pow_difficulty(&self) -> u8 {
+        self.pow_difficulty
     }
 
     #[instrument(skip_all, level = "debug", ret)]
@@ -80,16 +238,126 @@ impl RelayClient {
         self.client.relays().await
     }
 
+    /// Adds relays to the pool and connects to them, returns relays which were not in the pool before.
     #[instrument(skip_all, level = "debug", ret)]
-    pub async fn publish_event(&self, event: &Event) -> crate::error::Result<EventId> {
-        if !self.client.has_signer().await {
-            return Err(NostrRelayError::MissingSigner);
+    pub async fn add_relays(
+        &self,
+        relay_urls: impl IntoIterator<Item = RelayUrl>,
+    ) -> crate::error::Result<Vec<RelayUrl>> {
+        let mut added = Vec::new();
+
+        for url in relay_urls {
+            let added_to_pool = self
+                .client
+                .add_relay(&url)
+                .await
+                .map_err(|err| NostrRelayError::FailedToAddRelay {
+                    url: url.to_string(),
+                    source: err,
+                })?;
+            if added_to_pool {
+                self.client
+                    .connect_relay(&url)
+                    .await
+                    .map_err(NostrRelayError::NostrClientFailure)?;
+                added.push(url);
+            }
         }
 
-        let event_id = self.client.send_event(event).await?;
-        let event_id = Self::handle_relay_output(event_id)?;
+        Ok(added)
+    }
+
+    /// Returns health report for every relay in the pool.
+    #[instrument(skip_all, level = "debug", ret)]
+    pub async fn get_relays_health(&self) -> Vec<RelayHealth> {
+        let mut health = self
+            .client
+            .relays()
+            .await
+            .iter()
+            .map(|(url, relay)| self.health.health(url, relay.status(), relay.stats().latency()))
+            .collect::<Vec<RelayHealth>>();
+        health.sort_by(|a, b| a.url.as_str().cmp(b.url.as_str()));
+
+        health
+    }
+
+    /// Sends a lightweight request to every relay so that health report reflects current state.
+    #[instrument(skip_all, level = "debug", ret)]
+    pub async fn probe_relays(&self) -> Vec<RelayHealth> {
+        let relays = self.client.relays().await;
+
+        let probes = relays.iter().map(|(url, relay)| async move {
+            let started_at = Instant::now();
+            let res = relay
+                .fetch_events(Filter::new().limit(1), self.timeout, ReqExitPolicy::ExitOnEOSE)
+                .await;
+            match res {
+                Ok(_) => self
+                    .health
+                    .record_success(url, RelayOperation::Fetch, Some(started_at.elapsed())),
+                Err(err) => self.health.record_failure(url, RelayOperation::Fetch, err),
+            }
+        });
+        join_all(probes).await;
 
-        Ok(event_id)
+        self.get_relays_health().await
+    }
+
+    /// Returns NIP-11 information document of the relay, fetching it only once per client.
+    #[instrument(skip(self), level = "debug", ret)]
+    pub async fn get_relay_info(&self, url: &RelayUrl) -> crate::error::Result<RelayInformationDocument> {
+        if let Some(document) = self.relays_info.read().unwrap_or_else(PoisonError::into_inner).get(url) {
+            return Ok(document.clone());
+        }
+
+        let document = fetch_relay_information(&self.http_client, url, self.timeout).await?;
+        self.relays_info
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(url.clone(), document.clone());
+
+        Ok(document)
+    }
+
+    #[instrument(skip_all, level = "debug")]
+    pub async fn get_relays_info(&self) -> Vec<(RelayUrl, crate::error::Result<RelayInformationDocument>)> {
+        let mut urls = self.client.relays().await.into_keys().collect::<Vec<RelayUrl>>();
+        urls.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        let documents = join_all(urls.iter().map(|url| self.get_relay_info(url))).await;
+
+        urls.into_iter().zip(documents).collect()
+    }
+
+    #[instrument(skip_all, level = "debug", ret)]
+    pub async fn publish_event(&self, event: &Event) -> crate::error::Result<EventId> {
+        let relays = self.healthy_relays().await;
+
+        self.publish_event_to(relays, event).await
+    }
+
+    /// Publishes event which relies on NIP-40 expiration, checking relays capabilities beforehand.
+    #[instrument(skip_all, level = "debug", ret)]
+    pub async fn publish_expiring_event(&self, event: &Event) -> crate::error::Result<EventId> {
+        let mut relays = self.healthy_relays().await;
+
+        let urls = relays.keys().cloned().collect::<Vec<RelayUrl>>();
+        let documents = join_all(urls.iter().map(|url| self.get_relay_info(url))).await;
+
+        for (url, document) in urls.into_iter().zip(documents) {
+            let document = document
+                .inspect_err(|err| tracing::debug!(url = %url, error = %err, "Relay capabilities are unknown"))
+                .ok();
+            if !self
+                .unsupported_expiration_policy
+                .accepts_relay(&url, document.as_ref())
+            {
+                relays.remove(&url);
+            }
+        }
+
+        self.publish_event_to(relays, event).await
     }
 
     #[instrument(skip(self), level = "debug")]
@@ -98,7 +366,13 @@ impl RelayClient {
         filter: Filter,
         opts: Option<SubscribeAutoCloseOptions>,
     ) -> crate::error::Result<SubscriptionId> {
-        Ok(self.client.subscribe(filter, opts).await?.val)
+        let output = self
+            .client
+            .subscribe(filter, opts)
+            .await
+            .map_err(NostrRelayError::NostrClientFailure)?;
+
+        Ok(output.val)
     }
 
     #[instrument(skip(self), level = "debug")]
@@ -113,11 +387,164 @@ impl RelayClient {
         Ok(())
     }
 
-    /// TODO: handle error
-    #[instrument(level = "debug")]
-    fn handle_relay_output<T: Debug>(output: Output<T>) -> crate::error::Result<T> {
+    /// Events are signed beforehand, so publishing pre-signed events does not require a signer.
+    ///
+    /// Relays which failed with a transient reason are retried according to the retry policy.
+    async fn publish_event_to(&self, relays: HashMap<RelayUrl, Relay>, event: &Event) -> crate::error::Result<EventId> {
+        if relays.is_empty() {
+            return Err(NostrRelayError::NoRelaysConnected {
+                operation: RelayOperation::Publish,
+            });
+        }
+        if relays.len() < self.min_healthy_relays {
+            return Err(NostrRelayError::NotEnoughHealthyRelays {
+                required: self.min_healthy_relays,
+                available: relays.len(),
+            });
+        }
+
+        let started_at = Instant::now();
+        let mut pending = relays.into_keys().collect::<Vec<RelayUrl>>();
+        let mut failed = HashMap::new();
+        let mut accepted = false;
+        let mut attempt = 1;
+        loop {
+            let span = tracing::debug_span!("relay_attempt", operation = %RelayOperation::Publish, attempt);
+            match self.client.send_event_to(&pending, event).instrument(span).await {
+                Ok(output) => {
+                    let (accepted_by, rejected_by) = self.handle_relay_output(output);
+                    accepted |= !accepted_by.is_empty();
+                    for url in &accepted_by {
+                        failed.remove(url);
+                    }
+                    failed.extend(rejected_by);
+                }
+                Err(err) => {
+                    let err = NostrRelayError::NostrClientFailure(err);
+                    if !err.is_retryable() {
+                        count_rejections(failed.keys().chain(&pending).collect::<HashSet<&RelayUrl>>());
+                        return Err(err);
+                    }
+                    failed.extend(pending.iter().map(|url| (url.clone(), err.to_string())));
+                }
+            }
+
+            pending = failed
+                .iter()
+                .map(|(url, reason)| RelayFailure {
+                    url: url.clone(),
+                    reason: reason.clone(),
+                })
+                .filter(RelayFailure::is_transient)
+                .map(|x| x.url)
+                .collect();
+            let delay = match pending.is_empty() {
+                true => None,
+                false => self.retry_policy.next_delay(attempt, started_at),
+            };
+
+            match delay {
+                Some(delay) => {
+                    tracing::warn!(
+                        attempt,
+                        relays = pending.len(),
+                        ?delay,
+                        "Relays failed to accept event, retrying"
+                    );
+                    for url in &pending {
+                        metrics::PUBLISH_RETRIES.increment(&[("relay", url.as_str())]);
+                    }
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None if accepted => {
+                    count_rejections(failed.keys());
+                    return Ok(event.id);
+                }
+                None => {
+                    count_rejections(failed.keys());
+                    let failures = failed
+                        .into_iter()
+                        .map(|(url, reason)| RelayFailure { url, reason })
+                        .collect();
+                    return Err(NostrRelayError::AllRelaysRejected {
+                        operation: RelayOperation::Publish,
+                        failures,
+                    });
+                }
+            }
+        }
+    }
+
+    async fn healthy_relays(&self) -> HashMap<RelayUrl, Relay> {
+        self.client
+            .relays()
+            .await
+            .into_iter()
+            .filter(|(url, relay)| self.health.health(url, relay.status(), None).is_healthy())
+            .collect()
+    }
+
+    /// Records outcome per relay, returns relays which accepted the event and relays which did not.
+    ///
+    /// Relays replying with `duplicate:` already store the event, so they are counted as accepted.
+    #[instrument(skip(self), level = "debug")]
+    fn handle_relay_output<T: Debug>(&self, output: Output<T>) -> (HashSet<RelayUrl>, HashMap<RelayUrl, String>) {
         tracing::debug!(output = ?output, "Handling Relay output.");
 
-        Ok(output.val)
+        let mut success = output.success;
+        let mut failed = HashMap::new();
+        for (url, reason) in output.failed {
+            let failure = RelayFailure { url, reason };
+            match failure.is_duplicate() {
+                true => {
+                    tracing::debug!(url = %failure.url, reason = %failure.reason, "Relay already stores the event");
+                    success.insert(failure.url);
+                }
+                false => {
+                    failed.insert(failure.url, failure.reason);
+                }
+            }
+        }
+
+        for url in &success {
+            self.health.record_success(url, RelayOperation::Publish, None);
+            metrics::EVENTS_PUBLISHED.increment(&[("relay", url.as_str())]);
+        }
+        for (url, err) in &failed {
+            self.health.record_failure(url, RelayOperation::Publish, err);
+        }
+
+        (success, failed)
+    }
+}
+
+/// Counts relays which did not accept the event after the final publish attempt.
+fn count_rejections<'a>(urls: impl IntoIterator<Item = &'a RelayUrl>) {
+    for url in urls {
+        metrics::EVENTS_REJECTED.increment(&[("relay", url.as_str())]);
     }
 }
+
+/// Counts connections re-established after the first one per relay until the client is dropped.
+fn count_reconnects(monitor: &Monitor) {
+    let mut notifications = monitor.subscribe();
+    tokio::spawn(async move {
+        let mut connected = HashSet::new();
+        loop {
+            match notifications.recv().await {
+                Ok(MonitorNotification::StatusChanged {
+                    relay_url,
+                    status: RelayStatus::Connected,
+                }) => {
+                    if !connected.insert(relay_url.clone()) {
+                        tracing::debug!(url = %relay_url, "Relay reconnected");
+                        metrics::RELAY_RECONNECTS.increment(&[("relay", relay_url.as_str())]);
+                    }
+                }
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
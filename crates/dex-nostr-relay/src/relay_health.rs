@@ -0,0 +1,172 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+use nostr::RelayUrl;
+use nostr_sdk::RelayStatus;
+
+/// Amount of the most recent operations taken into account for success rates.
+pub const HEALTH_WINDOW_SIZE: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelayOperation {
+    Publish,
+    Fetch,
+}
+
+impl fmt::Display for RelayOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RelayOperation::Publish => write!(f, "publish"),
+            RelayOperation::Fetch => write!(f, "fetch"),
+        }
+    }
+}
+
+/// Point-in-time health report of a single relay.
+#[derive(Debug, Clone)]
+pub struct RelayHealth {
+    pub url: RelayUrl,
+    pub status: RelayStatus,
+    pub latency: Option<Duration>,
+    pub publish_success_rate: Option<f64>,
+    pub fetch_success_rate: Option<f64>,
+    pub consecutive_failures: usize,
+    pub last_error: Option<String>,
+    pub demoted: bool,
+}
+
+impl RelayHealth {
+    pub fn is_healthy(&self) -> bool {
+        !self.demoted && !matches!(self.status, RelayStatus::Terminated | RelayStatus::Banned)
+    }
+}
+
+#[derive(Debug, Default)]
+struct RelayRecord {
+    publish: VecDeque<bool>,
+    fetch: VecDeque<bool>,
+    latency: Option<Duration>,
+    consecutive_failures: usize,
+    last_error: Option<String>,
+    demoted_until: Option<Instant>,
+}
+
+impl RelayRecord {
+    fn outcomes_mut(&mut self, operation: RelayOperation) -> &mut VecDeque<bool> {
+        match operation {
+            RelayOperation::Publish => &mut self.publish,
+            RelayOperation::Fetch => &mut self.fetch,
+        }
+    }
+
+    fn is_demoted(&self, now: Instant) -> bool {
+        self.demoted_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Keeps track of recent operation outcomes per relay and demotes relays which keep failing.
+///
+/// A relay is demoted after `demote_after_failures` consecutive failures and is skipped until
+/// `demotion_cooldown` elapses, after which it gets another chance.
+#[derive(Debug)]
+pub struct RelayHealthTracker {
+    records: RwLock<HashMap<RelayUrl, RelayRecord>>,
+    demote_after_failures: usize,
+    demotion_cooldown: Duration,
+}
+
+impl RelayHealthTracker {
+    pub fn new(demote_after_failures: usize, demotion_cooldown: Duration) -> Self {
+        Self {
+            records: RwLock::new(HashMap::new()),
+            demote_after_failures,
+            demotion_cooldown,
+        }
+    }
+
+    pub fn record_success(&self, url: &RelayUrl, operation: RelayOperation, latency: Option<Duration>) {
+        let mut records = self.records.write().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let record = records.entry(url.clone()).or_default();
+
+        push_outcome(record.outcomes_mut(operation), true);
+        record.consecutive_failures = 0;
+        record.demoted_until = None;
+        if latency.is_some() {
+            record.latency = latency;
+        }
+    }
+
+    pub fn record_failure(&self, url: &RelayUrl, operation: RelayOperation, error: impl ToString) {
+        let mut records = self.records.write().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let record = records.entry(url.clone()).or_default();
+
+        push_outcome(record.outcomes_mut(operation), false);
+        record.consecutive_failures += 1;
+        record.last_error = Some(error.to_string());
+
+        if self.demote_after_failures > 0 && record.consecutive_failures >= self.demote_after_failures {
+            let now = Instant::now();
+            if !record.is_demoted(now) {
+                tracing::warn!(
+                    url = %url,
+                    failures = record.consecutive_failures,
+                    "Demoting relay after consecutive failures"
+                );
+            }
+            record.demoted_until = Some(now + self.demotion_cooldown);
+        }
+    }
+
+    pub fn is_demoted(&self, url: &RelayUrl) -> bool {
+        let records = self.records.read().unwrap_or_else(std::sync::PoisonError::into_inner);
+        records.get(url).is_some_and(|record| record.is_demoted(Instant::now()))
+    }
+
+    /// Combines recorded outcomes with the current connection state reported by the pool.
+    pub fn health(&self, url: &RelayUrl, status: RelayStatus, pool_latency: Option<Duration>) -> RelayHealth {
+        let records = self.records.read().unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+
+        match records.get(url) {
+            None => RelayHealth {
+                url: url.clone(),
+                status,
+                latency: pool_latency,
+                publish_success_rate: None,
+                fetch_success_rate: None,
+                consecutive_failures: 0,
+                last_error: None,
+                demoted: false,
+            },
+            Some(record) => RelayHealth {
+                url: url.clone(),
+                status,
+                latency: pool_latency.or(record.latency),
+                publish_success_rate: success_rate(&record.publish),
+                fetch_success_rate: success_rate(&record.fetch),
+                consecutive_failures: record.consecutive_failures,
+                last_error: record.last_error.clone(),
+                demoted: record.is_demoted(now),
+            },
+        }
+    }
+}
+
+fn push_outcome(outcomes: &mut VecDeque<bool>, outcome: bool) {
+    if outcomes.len() == HEALTH_WINDOW_SIZE {
+        outcomes.pop_front();
+    }
+    outcomes.push_back(outcome);
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn success_rate(outcomes: &VecDeque<bool>) -> Option<f64> {
+    if outcomes.is_empty() {
+        return None;
+    }
+
+    let succeeded = outcomes.iter().filter(|x| **x).count();
+    Some(succeeded as f64 / outcomes.len() as f64)
+}
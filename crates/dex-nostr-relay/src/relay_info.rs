@@ -0,0 +1,74 @@
+use crate::error::NostrRelayError;
+
+use std::time::Duration;
+
+use nostr::RelayUrl;
+use nostr::nips::nip11::RelayInformationDocument;
+
+/// NIP-40: Expiration timestamp
+pub const NIP_EXPIRATION: u16 = 40;
+
+const NOSTR_JSON_MIME: &str = "application/nostr+json";
+
+/// Defines what to do with relays that do not support expiration while publishing orders.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UnsupportedExpirationPolicy {
+    /// Publish to the relay anyway, but emit a warning
+    #[default]
+    Warn,
+    /// Do not publish to the relay
+    Skip,
+}
+
+impl UnsupportedExpirationPolicy {
+    /// Whether expiring events are published to the relay, `document` is `None` if its capabilities are unknown.
+    pub fn accepts_relay(self, url: &RelayUrl, document: Option<&RelayInformationDocument>) -> bool {
+        if document.is_some_and(|document| supports_nip(document, NIP_EXPIRATION)) {
+            return true;
+        }
+
+        match self {
+            UnsupportedExpirationPolicy::Warn => {
+                tracing::warn!(url = %url, "Relay does not advertise expiration support (NIP-40)");
+                true
+            }
+            UnsupportedExpirationPolicy::Skip => {
+                tracing::warn!(url = %url, "Skipping relay without expiration support (NIP-40)");
+                false
+            }
+        }
+    }
+}
+
+pub fn supports_nip(document: &RelayInformationDocument, nip: u16) -> bool {
+    document.supported_nips.as_ref().is_some_and(|nips| nips.contains(&nip))
+}
+
+/// Fetches NIP-11 information document, served on the same address as websocket but over http(s).
+pub async fn fetch_relay_information(
+    http_client: &reqwest::Client,
+    url: &RelayUrl,
+    timeout: Duration,
+) -> crate::error::Result<RelayInformationDocument> {
+    let http_url = url
+        .as_str()
+        .replacen("wss://", "https://", 1)
+        .replacen("ws://", "http://", 1);
+
+    let to_error = |source: reqwest::Error| NostrRelayError::FailedToFetchRelayInformation {
+        url: url.clone(),
+        source,
+    };
+
+    http_client
+        .get(http_url)
+        .header(reqwest::header::ACCEPT, NOSTR_JSON_MIME)
+        .timeout(timeout)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(to_error)?
+        .json::<RelayInformationDocument>()
+        .await
+        .map_err(to_error)
+}
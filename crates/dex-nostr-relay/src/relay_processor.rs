@@ -1,13 +1,44 @@
+use crate::archive::{ArchiveQuery, ImportReport};
+use crate::batch::BatchPlacementReport;
+use crate::compiler_provenance::{CompilerAllowList, CompilerStatus, validate_compiler_tag};
+use crate::contract_verification::ContractSummary;
+use crate::error::NostrRelayError;
 use crate::handlers;
+use crate::inspection::{self, EventInspection};
+use crate::maker_registry::MakerRegistry;
+use crate::offline;
+use crate::option_terms::{OptionTerms, OptionTermsFilter};
+use crate::oracle::{AggregatedPrice, AttestationQuery, OracleSet, PriceAttestation, PriceAttestationTags};
+use crate::query::QueryOptions;
 use crate::relay_client::{ClientConfig, RelayClient};
+use crate::relay_health::RelayHealth;
+use crate::reputation::MakerReputation;
+use crate::rfq::{Quote, QuoteRequest, QuoteRequestTags, QuoteTags};
+use crate::settlement::{SettlementParams, SettlementResult, SettlementRole};
+use crate::swap::{self, SwapProposalParams, SwapSummary};
+use crate::tags::{required_tag_value, tag_value};
+use crate::types::{CustomKind, MakerOrderKind, TakerOrderKind};
 
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::time::Duration;
+
+use nostr::nips::nip11::RelayInformationDocument;
+use nostr::nips::nip65::RelayMetadata;
 use nostr::prelude::IntoNostrSigner;
-use nostr::{EventId, PublicKey, TryIntoUrl};
+use nostr::{Event, EventId, PublicKey, RelayUrl, Timestamp, TryIntoUrl, UnsignedEvent};
 
+use elements::pset::PartiallySignedTransaction;
+use elements::{Script, Transaction};
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
 use nostr_sdk::prelude::Events;
 
 pub struct RelayProcessor {
     relay_client: RelayClient,
+    maker_registry: Option<Box<dyn MakerRegistry>>,
+    compiler_allow_list: Option<CompilerAllowList>,
+    oracle_set: Option<OracleSet>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -15,33 +46,278 @@ pub struct OrderPlaceEventTags {
     pub asset_to_sell: String,
     pub asset_to_buy: String,
     pub price: u64,
+    /// Unix timestamp of the `expiry` tag, not written if `0`
     pub expiry: u64,
+    /// Compiler of the order contract, placement fails if it is empty
     pub compiler_name: String,
+    /// Hex encoded deterministic build hash of the compiler, required for placement
     pub compiler_build_hash: String,
+    /// Base64 encoded Simplicity program of the order contract, without witness data
+    pub contract_program: Option<String>,
+    /// Commitment Merkle root of the contract as advertised by the maker, checked against the program by takers
+    pub contract_cmr: Option<String>,
+    /// Liquid address of the contract output
+    pub contract_address: Option<String>,
+    /// Amount of `asset_to_sell` offered by the order, unlimited if absent
+    pub amount: Option<u64>,
+    /// Present if the order offers an option contract
+    pub option_terms: Option<OptionTerms>,
+}
+
+/// Client-side criteria applied to orders returned by relays.
+#[derive(Debug, Default, Clone)]
+pub struct OrderFilter {
+    /// Drop orders with NIP-13 proof-of-work below this difficulty
+    pub min_pow_difficulty: Option<u8>,
+    /// Keep only orders from makers registered in the Maker Identity Registry
+    pub registered_only: bool,
+    /// Compute maker reputation for every listed order
+    pub with_reputation: bool,
+    /// Weight reputation by the follow list of this public key
+    pub web_of_trust_root: Option<PublicKey>,
+    /// Keep only orders compiled by toolchains trusted in the compiler allow-list
+    pub trusted_compilers_only: bool,
+    /// Keep only option orders with matching terms
+    pub option_terms: Option<OptionTermsFilter>,
+}
+
+/// Order event enriched with information about its maker.
+#[derive(Debug, Clone)]
+pub struct ListedOrder {
+    pub event: Event,
+    /// Whether maker is registered, `None` if registry is not configured or lookup failed
+    pub maker_registered: Option<bool>,
+    /// Maker reputation, present if requested with [`OrderFilter::with_reputation`]
+    pub maker_reputation: Option<MakerReputation>,
+    /// Trust level of the order compiler, `None` if compiler allow-list is not configured
+    pub compiler_status: Option<CompilerStatus>,
+}
+
+impl TryFrom<&Event> for OrderPlaceEventTags {
+    type Error = NostrRelayError;
+
+    /// Parses maker order, validating that mandatory tags are present and well-formed.
+    fn try_from(event: &Event) -> crate::error::Result<Self> {
+        if event.kind != MakerOrderKind::get_kind() {
+            return Err(NostrRelayError::UnexpectedEventKind {
+                expected: MakerOrderKind::get_u16(),
+                got: event.kind.as_u16(),
+            });
+        }
+
+        let compiler_name = required_tag_value(event, "compiler", 1)?.to_string();
+        let compiler_build_hash = required_tag_value(event, "compiler", 2)?.to_string();
+        validate_compiler_tag(&compiler_name, &compiler_build_hash)?;
+
+        let price = required_tag_value(event, "price", 1)?;
+        let price = price.parse::<u64>().map_err(|err| NostrRelayError::InvalidOrderTag {
+            tag: "price".to_string(),
+            err_msg: format!("'{price}' is not a valid price, {err}"),
+        })?;
+
+        let amount = tag_value(event, "amount", 1)
+            .map(|amount| {
+                amount.parse::<u64>().map_err(|err| NostrRelayError::InvalidOrderTag {
+                    tag: "amount".to_string(),
+                    err_msg: format!("'{amount}' is not a valid amount, {err}"),
+                })
+            })
+            .transpose()?;
+
+        let expiry = match tag_value(event, "expiry", 1) {
+            None => 0,
+            Some(expiry) => expiry.parse::<u64>().map_err(|err| NostrRelayError::InvalidOrderTag {
+                tag: "expiry".to_string(),
+                err_msg: format!("'{expiry}' is not a valid timestamp, {err}"),
+            })?,
+        };
+
+        Ok(Self {
+            asset_to_sell: required_tag_value(event, "asset_to_sell", 1)?.to_string(),
+            asset_to_buy: required_tag_value(event, "asset_to_buy", 1)?.to_string(),
+            price,
+            expiry,
+            compiler_name,
+            compiler_build_hash,
+            contract_program: tag_value(event, "contract", 1).map(ToString::to_string),
+            contract_cmr: tag_value(event, "cmr", 1).map(ToString::to_string),
+            contract_address: tag_value(event, "contract_address", 1).map(ToString::to_string),
+            amount,
+            option_terms: OptionTerms::from_event(event)?,
+        })
+    }
+}
+
+/// Maker order together with the verification summary of its contract.
+#[derive(Debug, Clone)]
+pub struct InspectedOrder {
+    pub event: Event,
+    pub tags: OrderPlaceEventTags,
+    pub contract: ContractSummary,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct OrderReplyEventTags {
+    /// Txid of the funding transaction, the tag is omitted if empty
     pub tx_id: String,
+    /// Base64 encoded PSET proposing atomic swap instead of funding the order beforehand
+    pub swap_pset: Option<String>,
+}
+
+impl TryFrom<&Event> for OrderReplyEventTags {
+    type Error = NostrRelayError;
+
+    fn try_from(event: &Event) -> crate::error::Result<Self> {
+        if event.kind != TakerOrderKind::get_kind() {
+            return Err(NostrRelayError::UnexpectedEventKind {
+                expected: TakerOrderKind::get_u16(),
+                got: event.kind.as_u16(),
+            });
+        }
+
+        Ok(Self {
+            tx_id: tag_value(event, "tx_id", 1).unwrap_or_default().to_string(),
+            swap_pset: tag_value(event, "pset", 1).map(ToString::to_string),
+        })
+    }
 }
 
 impl RelayProcessor {
     pub async fn try_from_config(
-        relay_urls: impl IntoIterator<Item = impl TryIntoUrl>,
+        relay_urls: impl IntoIterator<Item = impl TryIntoUrl<Err: std::error::Error + Send + Sync + 'static> + fmt::Display>,
         keys: Option<impl IntoNostrSigner>,
         client_config: ClientConfig,
     ) -> crate::error::Result<Self> {
         Ok(RelayProcessor {
             relay_client: RelayClient::connect(relay_urls, keys, client_config).await?,
+            maker_registry: None,
+            compiler_allow_list: None,
+            oracle_set: None,
         })
     }
 
+    #[must_use]
+    pub fn with_compiler_allow_list(mut self, compiler_allow_list: CompilerAllowList) -> Self {
+        self.compiler_allow_list = Some(compiler_allow_list);
+        self
+    }
+
+    #[must_use]
+    pub fn with_oracle_set(mut self, oracle_set: OracleSet) -> Self {
+        self.oracle_set = Some(oracle_set);
+        self
+    }
+
+    #[must_use]
+    pub fn with_maker_registry(mut self, maker_registry: impl MakerRegistry + 'static) -> Self {
+        self.maker_registry = Some(Box::new(maker_registry));
+        self
+    }
+
     pub async fn place_order(&self, tags: OrderPlaceEventTags) -> crate::error::Result<EventId> {
         handlers::place_order::handle(&self.relay_client, tags).await
     }
 
-    pub async fn list_orders(&self) -> crate::error::Result<Events> {
-        handlers::list_orders::handle(&self.relay_client).await
+    /// Builds unsigned order for offline signing, the order expires in `expires_in` after building.
+    pub async fn build_order(
+        &self,
+        tags: OrderPlaceEventTags,
+        maker_pubkey: PublicKey,
+        expires_in: Duration,
+    ) -> crate::error::Result<UnsignedEvent> {
+        handlers::place_order::build(tags, maker_pubkey, self.relay_client.pow_difficulty(), expires_in).await
+    }
+
+    /// Builds unsigned reply for offline signing.
+    pub async fn build_reply(
+        &self,
+        maker_event_id: EventId,
+        maker_pubkey: PublicKey,
+        tags: OrderReplyEventTags,
+        taker_pubkey: PublicKey,
+    ) -> crate::error::Result<UnsignedEvent> {
+        handlers::reply_order::build(
+            maker_event_id,
+            maker_pubkey,
+            tags,
+            taker_pubkey,
+            self.relay_client.pow_difficulty(),
+        )
+        .await
+    }
+
+    /// Publishes order or reply signed offline, after checking its signature and expiration.
+    pub async fn publish_signed_event(&self, event: &Event) -> crate::error::Result<EventId> {
+        offline::verify_signed_event(event)?;
+
+        if event.kind == MakerOrderKind::get_kind() {
+            handlers::place_order::publish(&self.relay_client, event).await
+        } else {
+            handlers::reply_order::publish(&self.relay_client, event).await
+        }
+    }
+
+    /// Fetches DEX events matching the query, sorted chronologically for re-publishing.
+    pub async fn export_events(&self, query: &ArchiveQuery) -> crate::error::Result<Vec<Event>> {
+        handlers::archive::export(&self.relay_client, query).await
+    }
+
+    /// Re-publishes verified archive to the relays of the processor, expired events are skipped.
+    pub async fn import_events(&self, events: Vec<Event>) -> crate::error::Result<ImportReport> {
+        handlers::archive::import(&self.relay_client, events).await
+    }
+
+    /// Places orders concurrently, see [`BatchPlacementReport`] for orders which failed.
+    pub async fn place_orders(&self, orders: Vec<OrderPlaceEventTags>) -> crate::error::Result<BatchPlacementReport> {
+        handlers::place_order::handle_batch(&self.relay_client, orders).await
+    }
+
+    /// Places orders concurrently, cancelling the placed ones if any order of the batch fails.
+    pub async fn place_orders_all_or_nothing(
+        &self,
+        orders: Vec<OrderPlaceEventTags>,
+    ) -> crate::error::Result<BatchPlacementReport> {
+        handlers::place_order::handle_batch_all_or_nothing(&self.relay_client, orders).await
+    }
+
+    /// Withdraws own orders with a deletion request (NIP-09).
+    pub async fn cancel_orders(&self, order_ids: Vec<EventId>) -> crate::error::Result<EventId> {
+        handlers::cancel_orders::handle(&self.relay_client, order_ids, "").await
+    }
+
+    /// Lists orders from all pages of relay history, bounded by [`QueryOptions::limit`].
+    pub async fn list_orders(&self, filter: OrderFilter, options: &QueryOptions) -> crate::error::Result<Events> {
+        handlers::list_orders::handle(&self.relay_client, filter, options).await
+    }
+
+    /// Streams orders newest first, fetching older pages from relays on demand.
+    pub fn list_orders_stream(
+        &self,
+        filter: OrderFilter,
+        options: QueryOptions,
+    ) -> BoxStream<'_, crate::error::Result<Event>> {
+        handlers::list_orders::handle_stream(&self.relay_client, filter, options).boxed()
+    }
+
+    /// Lists orders annotated with maker registration status, see [`ListedOrder`].
+    pub async fn list_annotated_orders(
+        &self,
+        filter: OrderFilter,
+        options: &QueryOptions,
+    ) -> crate::error::Result<Vec<ListedOrder>> {
+        handlers::list_orders::handle_annotated(
+            &self.relay_client,
+            filter,
+            options,
+            self.maker_registry.as_deref(),
+            self.compiler_allow_list.as_ref(),
+        )
+        .await
+    }
+
+    /// Fetches maker order and verifies its Simplicity contract, see [`ContractSummary`].
+    pub async fn inspect_order(&self, event_id: EventId) -> crate::error::Result<InspectedOrder> {
+        handlers::inspect_order::handle(&self.relay_client, event_id).await
     }
 
     pub async fn reply_order(
@@ -53,11 +329,154 @@ impl RelayProcessor {
         handlers::reply_order::handle(&self.relay_client, maker_event_id, maker_pubkey, tags).await
     }
 
-    pub async fn get_order_replies(&self, event_id: EventId) -> crate::error::Result<Events> {
-        handlers::order_replies::handle(&self.relay_client, event_id).await
+    /// Fetches taker reply and validates its swap PSET against the order before countersigning.
+    pub async fn inspect_reply(
+        &self,
+        reply_event_id: EventId,
+        maker_script: &Script,
+    ) -> crate::error::Result<SwapSummary> {
+        handlers::inspect_reply::handle(&self.relay_client, reply_event_id, maker_script).await
+    }
+
+    /// Accepts taker reply as maker, signalling that the trade is settled on the maker side.
+    pub async fn accept_reply(&self, reply_event_id: EventId) -> crate::error::Result<EventId> {
+        handlers::accept_reply::handle(&self.relay_client, reply_event_id).await
+    }
+
+    pub async fn get_makers_reputation(
+        &self,
+        makers: BTreeSet<PublicKey>,
+        web_of_trust_root: Option<PublicKey>,
+    ) -> crate::error::Result<HashMap<PublicKey, MakerReputation>> {
+        handlers::reputation::handle(&self.relay_client, makers, web_of_trust_root).await
+    }
+
+    pub async fn get_order_replies(&self, event_id: EventId, options: &QueryOptions) -> crate::error::Result<Events> {
+        handlers::order_replies::handle(&self.relay_client, event_id, options).await
+    }
+
+    pub async fn get_events_by_id(&self, event_id: EventId, options: &QueryOptions) -> crate::error::Result<Events> {
+        handlers::get_events::ids::handle(&self.relay_client, event_id, options).await
+    }
+
+    /// Fetches the event and checks it against the DEX protocol.
+    pub async fn inspect_event(&self, event_id: EventId) -> crate::error::Result<EventInspection> {
+        // Expired events are inspected too, expiration is reported as a violation
+        let options = QueryOptions {
+            include_expired: true,
+            ..Default::default()
+        };
+        let event = handlers::get_events::ids::handle(&self.relay_client, event_id, &options)
+            .await?
+            .first_owned()
+            .ok_or(NostrRelayError::EventNotFound(event_id))?;
+
+        Ok(inspection::inspect_event(&event, Timestamp::now()))
+    }
+
+    pub async fn get_relays_status(&self) -> Vec<RelayHealth> {
+        self.relay_client.probe_relays().await
+    }
+
+    /// Returns relays advertised by the author in NIP-65 relay list for the given usage.
+    pub async fn get_relay_list(
+        &self,
+        author: PublicKey,
+        usage: Option<RelayMetadata>,
+    ) -> crate::error::Result<Vec<RelayUrl>> {
+        handlers::relay_list::handle(&self.relay_client, author, usage).await
+    }
+
+    /// Discovers author's NIP-65 relays and adds them to the pool, returns newly added relays.
+    ///
+    /// Use [`RelayMetadata::Write`] to fetch events published by the author (e.g. maker orders)
+    /// and [`RelayMetadata::Read`] to deliver events addressed to the author (e.g. order replies).
+    pub async fn use_relay_list(&self, author: PublicKey, usage: RelayMetadata) -> crate::error::Result<Vec<RelayUrl>> {
+        let relays = self.get_relay_list(author, Some(usage)).await?;
+
+        self.relay_client.add_relays(relays).await
+    }
+
+    /// Broadcasts request for quote as taker, see [`QuoteRequestTags`].
+    pub async fn request_quote(&self, tags: QuoteRequestTags) -> crate::error::Result<EventId> {
+        handlers::quotes::request(&self.relay_client, tags).await
+    }
+
+    pub async fn list_quote_requests(&self, options: &QueryOptions) -> crate::error::Result<Vec<QuoteRequest>> {
+        handlers::quotes::list_requests(&self.relay_client, options).await
+    }
+
+    /// Responds to the quote request as maker, optionally encrypting terms to the requester.
+    pub async fn quote(&self, request_id: EventId, tags: QuoteTags) -> crate::error::Result<EventId> {
+        handlers::quotes::quote(&self.relay_client, request_id, tags).await
+    }
+
+    pub async fn list_quotes(&self, request_id: EventId, options: &QueryOptions) -> crate::error::Result<Vec<Quote>> {
+        handlers::quotes::list(&self.relay_client, request_id, options).await
+    }
+
+    /// Publishes signed price attestation as an oracle.
+    pub async fn publish_price_attestation(&self, tags: PriceAttestationTags) -> crate::error::Result<EventId> {
+        handlers::oracle_attestations::publish(&self.relay_client, tags).await
+    }
+
+    /// Fetches attestations of the configured oracles, see [`OracleSet`].
+    pub async fn get_price_attestations(
+        &self,
+        query: &AttestationQuery,
+    ) -> crate::error::Result<Vec<PriceAttestation>> {
+        let oracle_set = self.oracle_set.as_ref().ok_or(NostrRelayError::MissingOracleSet)?;
+        handlers::oracle_attestations::handle(&self.relay_client, oracle_set, query).await
+    }
+
+    /// Fetches attestations and aggregates them into the median price agreed by the oracle set threshold.
+    pub async fn get_oracle_price(&self, query: &AttestationQuery) -> crate::error::Result<AggregatedPrice> {
+        let attestations = self.get_price_attestations(query).await?;
+        let oracle_set = self.oracle_set.as_ref().ok_or(NostrRelayError::MissingOracleSet)?;
+        oracle_set.aggregate(&attestations, query.timestamp)
+    }
+
+    /// Closes matched option as maker (settle) or taker (exercise) using the configured oracle set.
+    ///
+    /// Publishes settlement event linking the order, the reply and the txid of the proposed settlement,
+    /// broadcasting the settlement transaction is left to the wallet.
+    pub async fn settle_order(
+        &self,
+        role: SettlementRole,
+        reply_event_id: EventId,
+        params: SettlementParams,
+    ) -> crate::error::Result<SettlementResult> {
+        let oracle_set = self.oracle_set.as_ref().ok_or(NostrRelayError::MissingOracleSet)?;
+        handlers::settle_order::handle(&self.relay_client, oracle_set, role, reply_event_id, params).await
+    }
+
+    /// Builds unsigned swap PSET for the order, does not require connection to relays.
+    pub fn build_swap_proposal(
+        order: &OrderPlaceEventTags,
+        params: &SwapProposalParams,
+    ) -> crate::error::Result<PartiallySignedTransaction> {
+        swap::build_swap_pset(order, params)
+    }
+
+    /// Validates swap PSET proposed by the taker against maker's order before countersigning.
+    pub fn validate_swap_proposal(
+        pset: &PartiallySignedTransaction,
+        order: &OrderPlaceEventTags,
+        maker_script: &Script,
+    ) -> crate::error::Result<SwapSummary> {
+        swap::validate_swap_pset(pset, order, maker_script)
+    }
+
+    /// Combines PSETs signed by both parties and extracts the final swap transaction.
+    pub fn finalize_swap_proposal(
+        mut pset: PartiallySignedTransaction,
+        countersigned: PartiallySignedTransaction,
+    ) -> crate::error::Result<Transaction> {
+        pset.merge(countersigned)?;
+        swap::finalize_swap_pset(pset)
     }
 
-    pub async fn get_events_by_id(&self, event_id: EventId) -> crate::error::Result<Events> {
-        handlers::get_events::ids::handle(&self.relay_client, event_id).await
+    pub async fn get_relays_info(&self) -> Vec<(RelayUrl, crate::error::Result<RelayInformationDocument>)> {
+        self.relay_client.get_relays_info().await
     }
 }
@@ -0,0 +1,120 @@
+use crate::types::{CustomKind, MakerAcceptKind, MakerOrderKind, TakerOrderKind};
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+use nostr::{Event, EventId, Kind, PublicKey, TagKind, Timestamp};
+
+/// Weight applied to the score of makers outside of the web-of-trust follow list.
+pub const UNTRUSTED_MAKER_WEIGHT: f64 = 0.5;
+
+/// Metrics describing how a maker handled its orders, derived from DEX events on relays.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MakerReputation {
+    pub maker: PublicKey,
+    pub orders_placed: usize,
+    pub replies_received: usize,
+    /// Taker replies explicitly accepted by the maker, counted only if the accept agrees with the reply
+    pub trades_settled: usize,
+    /// Orders deleted (NIP-09) by the maker after a taker replied with funding
+    pub cancellations_after_funding: usize,
+    pub average_time_to_accept: Option<Duration>,
+    /// `1.0` for makers in the web-of-trust (or when it is not used), [`UNTRUSTED_MAKER_WEIGHT`] otherwise
+    pub trust_weight: f64,
+    /// Value in `[0, 1]`, `None` until the maker received at least one reply
+    pub score: Option<f64>,
+}
+
+/// Computes reputation of the maker from a mixed set of DEX events.
+///
+/// Expected events are maker orders, taker replies, maker accepts and NIP-09 deletions; unrelated
+/// events are ignored. `trusted_makers` is a web-of-trust follow list, `None` disables weighting.
+///
+/// Accepts are self-reported, so an accept is counted only if it names the order and the taker of
+/// the reply it accepts, and replies authored by the maker itself are ignored.
+#[allow(clippy::cast_precision_loss)]
+pub fn compute_reputation(
+    maker: PublicKey,
+    events: &[Event],
+    trusted_makers: Option<&HashSet<PublicKey>>,
+) -> MakerReputation {
+    let orders = events
+        .iter()
+        .filter(|x| x.pubkey == maker && x.kind == MakerOrderKind::get_kind())
+        .map(|x| x.id)
+        .collect::<HashSet<EventId>>();
+
+    let replies = events
+        .iter()
+        .filter(|x| x.kind == TakerOrderKind::get_kind() && x.pubkey != maker)
+        .filter_map(|x| {
+            let order_id = x.tags.event_ids().find(|id| orders.contains(id))?;
+            Some((x.id, (*order_id, x.pubkey, x.created_at)))
+        })
+        .collect::<HashMap<EventId, (EventId, PublicKey, Timestamp)>>();
+
+    let mut first_funded_at: HashMap<EventId, Timestamp> = HashMap::new();
+    for (order_id, _, created_at) in replies.values() {
+        let first = first_funded_at.entry(*order_id).or_insert(*created_at);
+        *first = (*first).min(*created_at);
+    }
+
+    let cancellations_after_funding = events
+        .iter()
+        .filter(|x| x.pubkey == maker && x.kind == Kind::EventDeletion)
+        .flat_map(|x| x.tags.event_ids().map(move |id| (id, x.created_at)))
+        .filter(|(id, deleted_at)| first_funded_at.get(id).is_some_and(|funded_at| deleted_at >= funded_at))
+        .map(|(id, _)| id)
+        .collect::<HashSet<&EventId>>()
+        .len();
+
+    let mut accepted = HashMap::new();
+    for accept in events
+        .iter()
+        .filter(|x| x.pubkey == maker && x.kind == MakerAcceptKind::get_kind())
+    {
+        let Some(reply_id) = accept
+            .tags
+            .find(TagKind::custom("reply_event_id"))
+            .and_then(|tag| tag.content())
+            .and_then(|id| EventId::from_str(id).ok())
+        else {
+            continue;
+        };
+        let Some((order_id, taker, replied_at)) = replies.get(&reply_id) else {
+            continue;
+        };
+        let corroborated =
+            accept.tags.event_ids().any(|id| id == order_id) && accept.tags.public_keys().any(|x| x == taker);
+        if corroborated {
+            let delay = accept.created_at.as_u64().saturating_sub(replied_at.as_u64());
+            accepted.entry(reply_id).or_insert(delay);
+        }
+    }
+
+    let average_time_to_accept =
+        (!accepted.is_empty()).then(|| Duration::from_secs(accepted.values().sum::<u64>() / accepted.len() as u64));
+
+    let trust_weight = match trusted_makers {
+        Some(trusted_makers) if !trusted_makers.contains(&maker) => UNTRUSTED_MAKER_WEIGHT,
+        _ => 1.0,
+    };
+
+    let score = (!replies.is_empty()).then(|| {
+        let settled_rate = (accepted.len() as f64 / replies.len() as f64).min(1.0);
+        let cancellation_rate = cancellations_after_funding as f64 / first_funded_at.len() as f64;
+        settled_rate * (1.0 - cancellation_rate) * trust_weight
+    });
+
+    MakerReputation {
+        maker,
+        orders_placed: orders.len(),
+        replies_received: replies.len(),
+        trades_settled: accepted.len(),
+        cancellations_after_funding,
+        average_time_to_accept,
+        trust_weight,
+        score,
+    }
+}
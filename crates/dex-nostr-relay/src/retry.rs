@@ -0,0 +1,96 @@
+use crate::relay_health::RelayOperation;
+
+use std::time::{Duration, Instant};
+
+use tracing::Instrument;
+
+/// Retry policy of relay operations which fail with retryable errors, see
+/// [`NostrRelayError::is_retryable`](crate::error::NostrRelayError::is_retryable).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts including the first one, `1` disables retries
+    pub max_attempts: usize,
+    /// Delay before the second attempt, doubled for every following one
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Randomize delays, so clients failed at the same moment do not retry in lockstep
+    pub jitter: bool,
+    /// Time budget of the whole operation, no attempt is started after it is exhausted
+    pub deadline: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+            jitter: true,
+            deadline: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn no_retries() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Delay after the failed `attempt` (starting from 1), grows exponentially up to `max_backoff`.
+    ///
+    /// With jitter the first half of the delay is fixed and the second one is random.
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        let exponent = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(exponent))
+            .min(self.max_backoff);
+        if !self.jitter {
+            return backoff;
+        }
+
+        let half = backoff / 2;
+        half + half.mul_f64(rand::random::<f64>())
+    }
+
+    /// Returns delay before the next attempt, `None` if attempts or the deadline are exhausted.
+    pub fn next_delay(&self, attempt: usize, started_at: Instant) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let delay = self.backoff(attempt);
+        (started_at.elapsed() + delay < self.deadline).then_some(delay)
+    }
+}
+
+/// Repeats the operation while it fails with a retryable error, every attempt runs in its own span.
+pub(crate) async fn retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    operation: RelayOperation,
+    mut attempt_operation: F,
+) -> crate::error::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = crate::error::Result<T>>,
+{
+    let started_at = Instant::now();
+    let mut attempt = 1;
+    loop {
+        let span = tracing::debug_span!("relay_attempt", %operation, attempt);
+        match attempt_operation().instrument(span).await {
+            Err(err) if err.is_retryable() => match policy.next_delay(attempt, started_at) {
+                Some(delay) => {
+                    tracing::warn!(%operation, attempt, ?delay, error = %err, "Relay operation failed, retrying");
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => return Err(err),
+            },
+            res => return res,
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use crate::error::NostrRelayError;
+use crate::tags::{required_tag_value, tag_value};
+use crate::types::{CustomKind, QuoteKind, QuoteRequestKind};
+
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::time::Duration;
+
+use elements::AssetId;
+use nostr::{Event, EventId, PublicKey, Tag, TagKind, Timestamp};
+use serde::{Deserialize, Serialize};
+
+/// Marker tag value of quotes with terms encrypted to the requester (NIP-44).
+pub const ENCRYPTED_QUOTE_SCHEME: &str = "nip44";
+
+/// Liquidity requested by the taker, `amount` is in `asset_to_buy` units.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteRequestTags {
+    pub asset_to_sell: String,
+    pub asset_to_buy: String,
+    pub amount: u64,
+    pub expires_in: Duration,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QuoteRequest {
+    pub event_id: EventId,
+    pub requester: PublicKey,
+    pub asset_to_sell: String,
+    pub asset_to_buy: String,
+    pub amount: u64,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Terms offered by the maker, `price` is in `asset_to_sell` units per unit of `asset_to_buy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuoteTerms {
+    pub price: u64,
+    /// May be lower than the requested amount for partial quotes
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteTags {
+    pub terms: QuoteTerms,
+    pub expires_in: Duration,
+    /// Encrypt terms to the requester, so only it can read the quote
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Quote {
+    pub event_id: EventId,
+    pub maker: PublicKey,
+    pub request_id: EventId,
+    pub terms: QuoteTerms,
+    pub encrypted: bool,
+    pub expires_at: Option<Timestamp>,
+}
+
+fn parse_amount(event: &Event, tag: &str) -> crate::error::Result<u64> {
+    let value = required_tag_value(event, tag, 1)?;
+    value.parse::<u64>().map_err(|err| NostrRelayError::InvalidOrderTag {
+        tag: tag.to_string(),
+        err_msg: format!("'{value}' is not a valid amount, {err}"),
+    })
+}
+
+fn invalid_tag(tag: &str, err_msg: impl Into<String>) -> NostrRelayError {
+    NostrRelayError::InvalidOrderTag {
+        tag: tag.to_string(),
+        err_msg: err_msg.into(),
+    }
+}
+
+fn check_kind<K: CustomKind>(event: &Event) -> crate::error::Result<()> {
+    if event.kind != K::get_kind() {
+        return Err(NostrRelayError::UnexpectedEventKind {
+            expected: K::get_u16(),
+            got: event.kind.as_u16(),
+        });
+    }
+    Ok(())
+}
+
+impl QuoteRequestTags {
+    /// Checks that both assets are valid asset ids and some amount is requested.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        for (tag, asset) in [
+            ("asset_to_sell", &self.asset_to_sell),
+            ("asset_to_buy", &self.asset_to_buy),
+        ] {
+            AssetId::from_str(asset)
+                .map_err(|err| invalid_tag(tag, format!("'{asset}' is not a valid asset id, {err}")))?;
+        }
+        if self.amount == 0 {
+            return Err(invalid_tag("amount", "requested amount must be positive"));
+        }
+        Ok(())
+    }
+
+    pub fn to_tags(&self) -> Vec<Tag> {
+        vec![
+            Tag::custom(
+                TagKind::Custom(Cow::from("asset_to_sell")),
+                [self.asset_to_sell.clone()],
+            ),
+            Tag::custom(TagKind::Custom(Cow::from("asset_to_buy")), [self.asset_to_buy.clone()]),
+            Tag::custom(TagKind::Custom(Cow::from("amount")), [self.amount.to_string()]),
+        ]
+    }
+}
+
+impl TryFrom<&Event> for QuoteRequest {
+    type Error = NostrRelayError;
+
+    fn try_from(event: &Event) -> crate::error::Result<Self> {
+        check_kind::<QuoteRequestKind>(event)?;
+
+        Ok(Self {
+            event_id: event.id,
+            requester: event.pubkey,
+            asset_to_sell: required_tag_value(event, "asset_to_sell", 1)?.to_string(),
+            asset_to_buy: required_tag_value(event, "asset_to_buy", 1)?.to_string(),
+            amount: parse_amount(event, "amount")?,
+            expires_at: event.tags.expiration().copied(),
+        })
+    }
+}
+
+impl QuoteTerms {
+    /// Checks that the terms have positive price and do not quote more than requested.
+    pub fn validate_for(&self, request: &QuoteRequest) -> crate::error::Result<()> {
+        if self.price == 0 {
+            return Err(invalid_tag("price", "quoted price must be positive"));
+        }
+        if self.amount == 0 || self.amount > request.amount {
+            return Err(invalid_tag(
+                "amount",
+                format!(
+                    "quoted amount {} must be positive and not above requested {}",
+                    self.amount, request.amount
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn to_tags(self) -> Vec<Tag> {
+        vec![
+            Tag::custom(TagKind::Custom(Cow::from("price")), [self.price.to_string()]),
+            Tag::custom(TagKind::Custom(Cow::from("amount")), [self.amount.to_string()]),
+        ]
+    }
+}
+
+impl Quote {
+    /// Returns `true` if the quote terms are encrypted and have to be decrypted by the requester.
+    pub fn is_encrypted_event(event: &Event) -> bool {
+        tag_value(event, "encrypted", 1) == Some(ENCRYPTED_QUOTE_SCHEME)
+    }
+
+    /// Parses quote event, `decrypted_content` must be provided for encrypted quotes.
+    pub fn from_event(event: &Event, decrypted_content: Option<&str>) -> crate::error::Result<Self> {
+        check_kind::<QuoteKind>(event)?;
+
+        let request_id = *event
+            .tags
+            .event_ids()
+            .next()
+            .ok_or_else(|| NostrRelayError::InvalidOrderTag {
+                tag: "e".to_string(),
+                err_msg: "quote does not reference request".to_string(),
+            })?;
+
+        let encrypted = Self::is_encrypted_event(event);
+        let terms = match (encrypted, decrypted_content) {
+            (false, _) => QuoteTerms {
+                price: parse_amount(event, "price")?,
+                amount: parse_amount(event, "amount")?,
+            },
+            (true, Some(content)) => serde_json::from_str(content).map_err(|err| NostrRelayError::InvalidOrderTag {
+                tag: "encrypted".to_string(),
+                err_msg: format!("failed to parse decrypted terms, {err}"),
+            })?,
+            (true, None) => {
+                return Err(NostrRelayError::InvalidOrderTag {
+                    tag: "encrypted".to_string(),
+                    err_msg: "quote terms are encrypted".to_string(),
+                });
+            }
+        };
+
+        Ok(Self {
+            event_id: event.id,
+            maker: event.pubkey,
+            request_id,
+            terms,
+            encrypted,
+            expires_at: event.tags.expiration().copied(),
+        })
+    }
+}
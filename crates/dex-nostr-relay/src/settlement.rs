@@ -0,0 +1,246 @@
+use crate::contract_verification::contract_script_pubkey;
+use crate::error::NostrRelayError;
+use crate::option_terms::{OptionStyle, OptionTerms, OptionType, Settlement};
+use crate::oracle::AggregatedPrice;
+use crate::relay_processor::{OrderPlaceEventTags, OrderReplyEventTags};
+use crate::swap::SwapInput;
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use elements::pset::{Input, Output, PartiallySignedTransaction};
+use elements::{Address, AssetId, OutPoint, Script, TxOut, TxOutWitness, Txid, confidential};
+use nostr::{Event, Timestamp};
+
+/// Party closing the option, maker settles the written option and taker exercises the bought one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettlementRole {
+    Maker,
+    Taker,
+}
+
+/// Matched order together with the taker reply which funded it.
+#[derive(Debug, Clone)]
+pub struct OrderThread {
+    pub order: Event,
+    pub order_tags: OrderPlaceEventTags,
+    pub reply: Event,
+    pub reply_tags: OrderReplyEventTags,
+}
+
+/// Values of the settlement which are not published on relays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SettlementParams {
+    /// Output of the funding transaction (`tx_id` of the reply) locked by the contract
+    pub contract_vout: u32,
+    /// Explicit amount of `collateral_asset` locked by the contract, one contract per unit
+    pub collateral_amount: u64,
+    pub maker_script: Script,
+    pub taker_script: Script,
+    /// Fee paid by the settling party from `fee_input`, change is returned to its script
+    pub fee: u64,
+    /// Policy asset of the network (L-BTC), the network rejects fees paid in other assets
+    pub fee_asset: AssetId,
+    /// Explicit UTXO of `fee_asset` funding the fee, required if the fee is not zero
+    pub fee_input: Option<SwapInput>,
+    /// Accepted distance between oracle attestation and settlement time
+    pub oracle_tolerance: Duration,
+}
+
+/// Split of the contract collateral between the option holder (taker) and writer (maker).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettlementPayout {
+    pub taker_amount: u64,
+    pub maker_amount: u64,
+}
+
+/// Outcome of the settlement published on relays.
+#[derive(Debug, Clone)]
+pub struct SettlementResult {
+    pub event_id: nostr::EventId,
+    /// Txid of the settlement PSET, the transaction is not broadcast yet
+    pub proposed_txid: Txid,
+    /// Base64 encoded settlement PSET, witness of the contract input is left to the wallet
+    pub pset: String,
+    pub oracle_price: AggregatedPrice,
+    pub payout: SettlementPayout,
+}
+
+fn settlement_error(err_msg: impl Into<String>) -> NostrRelayError {
+    NostrRelayError::InvalidSettlement {
+        err_msg: err_msg.into(),
+    }
+}
+
+impl fmt::Display for SettlementRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettlementRole::Maker => write!(f, "maker"),
+            SettlementRole::Taker => write!(f, "taker"),
+        }
+    }
+}
+
+/// Moment the oracle price is requested for, settlement time or `now` for early American exercise.
+///
+/// Height-based settlement cannot be checked without chain access and uses `now`.
+pub fn settlement_timestamp(
+    terms: &OptionTerms,
+    role: SettlementRole,
+    now: Timestamp,
+) -> crate::error::Result<Timestamp> {
+    match terms.settlement {
+        Settlement::Time(time) if time <= now.as_u64() => Ok(Timestamp::from(time)),
+        Settlement::Time(_) if role == SettlementRole::Taker && terms.style == OptionStyle::American => Ok(now),
+        Settlement::Time(time) => Err(settlement_error(format!(
+            "option cannot be closed by {role} before settlement time {time}"
+        ))),
+        Settlement::Height(height) => {
+            tracing::warn!(height, "Settlement height is not checked, make sure it is reached");
+            Ok(now)
+        }
+    }
+}
+
+/// Computes cash-settled payout in collateral units.
+///
+/// Holder receives intrinsic value `collateral × |price − strike|⁺ / price` (for a put the
+/// difference is reversed), capped at the locked collateral. Price is in settlement asset units
+/// per unit of collateral asset.
+pub fn compute_payout(
+    terms: &OptionTerms,
+    collateral_amount: u64,
+    price: u64,
+) -> crate::error::Result<SettlementPayout> {
+    if price == 0 {
+        return Err(settlement_error("oracle price is zero"));
+    }
+
+    let intrinsic = match terms.option_type {
+        OptionType::Call => price.saturating_sub(terms.strike_price),
+        OptionType::Put => terms.strike_price.saturating_sub(price),
+    };
+    let taker_amount = u128::from(collateral_amount) * u128::from(intrinsic) / u128::from(price);
+    let taker_amount = u64::try_from(taker_amount).unwrap_or(u64::MAX).min(collateral_amount);
+
+    Ok(SettlementPayout {
+        taker_amount,
+        maker_amount: collateral_amount - taker_amount,
+    })
+}
+
+/// Script locking the contract collateral, taken from advertised address or derived from CMR.
+pub fn contract_script(order_tags: &OrderPlaceEventTags) -> crate::error::Result<Script> {
+    if let Some(address) = &order_tags.contract_address {
+        let address = Address::from_str(address).map_err(|err| NostrRelayError::InvalidOrderTag {
+            tag: "contract_address".to_string(),
+            err_msg: format!("'{address}' is not a valid address, {err}"),
+        })?;
+        return Ok(address.script_pubkey());
+    }
+
+    let cmr = order_tags
+        .contract_cmr
+        .as_deref()
+        .ok_or_else(|| settlement_error("order does not advertise contract address or CMR"))?;
+    let cmr = simplicity::Cmr::from_str(cmr).map_err(|err| NostrRelayError::InvalidOrderTag {
+        tag: "cmr".to_string(),
+        err_msg: format!("'{cmr}' is not a valid CMR, {err}"),
+    })?;
+    Ok(contract_script_pubkey(cmr))
+}
+
+/// Builds settlement transaction skeleton spending the contract collateral and the fee input.
+///
+/// Collateral is split between the parties in full, the fee is paid separately in `fee_asset`.
+pub fn build_settlement_pset(
+    thread: &OrderThread,
+    role: SettlementRole,
+    params: &SettlementParams,
+    payout: SettlementPayout,
+) -> crate::error::Result<PartiallySignedTransaction> {
+    let terms = thread
+        .order_tags
+        .option_terms
+        .as_ref()
+        .ok_or_else(|| settlement_error("order is not an option"))?;
+    let collateral_asset =
+        AssetId::from_str(&terms.collateral_asset).map_err(|err| NostrRelayError::InvalidOrderTag {
+            tag: "collateral_asset".to_string(),
+            err_msg: err.to_string(),
+        })?;
+    let funding_txid = Txid::from_str(&thread.reply_tags.tx_id).map_err(|err| {
+        settlement_error(format!(
+            "reply does not carry valid funding txid '{}', {err}",
+            thread.reply_tags.tx_id
+        ))
+    })?;
+
+    let settling_script = match role {
+        SettlementRole::Maker => &params.maker_script,
+        SettlementRole::Taker => &params.taker_script,
+    };
+
+    let mut contract_input = Input::from_prevout(OutPoint::new(funding_txid, params.contract_vout));
+    contract_input.witness_utxo = Some(TxOut {
+        asset: confidential::Asset::Explicit(collateral_asset),
+        value: confidential::Value::Explicit(params.collateral_amount),
+        nonce: confidential::Nonce::Null,
+        script_pubkey: contract_script(&thread.order_tags)?,
+        witness: TxOutWitness::default(),
+    });
+
+    let mut pset = PartiallySignedTransaction::new_v2();
+    pset.add_input(contract_input);
+    let fee_change = match (&params.fee_input, params.fee) {
+        (_, 0) => 0,
+        (None, fee) => {
+            return Err(settlement_error(format!(
+                "fee {fee} requires an input of fee asset {}",
+                params.fee_asset
+            )));
+        }
+        (Some(fee_input), fee) => {
+            let (Some(asset), Some(value)) = (fee_input.utxo.asset.explicit(), fee_input.utxo.value.explicit()) else {
+                return Err(settlement_error("confidential fee input is not supported"));
+            };
+            if asset != params.fee_asset {
+                return Err(settlement_error(format!(
+                    "fee input holds asset {asset}, fee is paid in {}",
+                    params.fee_asset
+                )));
+            }
+            let change = value
+                .checked_sub(fee)
+                .ok_or_else(|| settlement_error(format!("fee input of {value} does not cover fee {fee}")))?;
+
+            let mut pset_input = Input::from_prevout(fee_input.outpoint);
+            pset_input.witness_utxo = Some(fee_input.utxo.clone());
+            pset.add_input(pset_input);
+            change
+        }
+    };
+
+    for (script, amount) in [
+        (&params.taker_script, payout.taker_amount),
+        (&params.maker_script, payout.maker_amount),
+    ] {
+        if amount > 0 {
+            pset.add_output(Output::new_explicit(script.clone(), amount, collateral_asset, None));
+        }
+    }
+    if params.fee > 0 {
+        pset.add_output(Output::new_explicit(Script::new(), params.fee, params.fee_asset, None));
+    }
+    if fee_change > 0 {
+        pset.add_output(Output::new_explicit(
+            settling_script.clone(),
+            fee_change,
+            params.fee_asset,
+            None,
+        ));
+    }
+
+    Ok(pset)
+}
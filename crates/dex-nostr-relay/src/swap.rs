@@ -0,0 +1,248 @@
+use crate::error::NostrRelayError;
+use crate::relay_processor::OrderPlaceEventTags;
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use elements::pset::{Input, Output, PartiallySignedTransaction};
+use elements::{AssetId, OutPoint, Script, Transaction, TxOut};
+
+/// Explicit (unblinded) UTXO spent in the swap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapInput {
+    pub outpoint: OutPoint,
+    pub utxo: TxOut,
+}
+
+/// Parameters of the swap proposed by the taker.
+///
+/// Change of every party is returned to its script, fee is paid by the taker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapProposalParams {
+    pub maker_inputs: Vec<SwapInput>,
+    pub taker_inputs: Vec<SwapInput>,
+    pub maker_script: Script,
+    pub taker_script: Script,
+    /// Amount of `asset_to_sell` bought from the maker, in contracts
+    pub sell_amount: u64,
+    pub fee: u64,
+    pub fee_asset: AssetId,
+}
+
+/// Maker side view of the swap, produced by [`validate_swap_pset`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapSummary {
+    /// Amount of `asset_to_sell` leaving the maker
+    pub sold_amount: u64,
+    /// Amount of `asset_to_buy` received by the maker
+    pub received_amount: u64,
+    pub fee: u64,
+}
+
+fn swap_error(err_msg: impl Into<String>) -> NostrRelayError {
+    NostrRelayError::InvalidSwapProposal {
+        err_msg: err_msg.into(),
+    }
+}
+
+fn order_asset(tag: &str, asset: &str) -> crate::error::Result<AssetId> {
+    AssetId::from_str(asset).map_err(|err| NostrRelayError::InvalidOrderTag {
+        tag: tag.to_string(),
+        err_msg: format!("'{asset}' is not a valid asset id, {err}"),
+    })
+}
+
+fn explicit_utxo(utxo: &TxOut) -> crate::error::Result<(AssetId, u64)> {
+    match (utxo.asset.explicit(), utxo.value.explicit()) {
+        (Some(asset), Some(value)) => Ok((asset, value)),
+        _ => Err(swap_error("confidential outputs are not supported")),
+    }
+}
+
+fn sum_by_asset<'a>(utxos: impl IntoIterator<Item = &'a TxOut>) -> crate::error::Result<HashMap<AssetId, u64>> {
+    let mut sums: HashMap<AssetId, u64> = HashMap::new();
+    for utxo in utxos {
+        let (asset, value) = explicit_utxo(utxo)?;
+        let sum = sums.entry(asset).or_default();
+        *sum = sum.checked_add(value).ok_or_else(|| swap_error("amount overflow"))?;
+    }
+    Ok(sums)
+}
+
+fn spend(change: &mut HashMap<AssetId, u64>, asset: AssetId, amount: u64, party: &str) -> crate::error::Result<()> {
+    let available = change.entry(asset).or_default();
+    *available = available
+        .checked_sub(amount)
+        .ok_or_else(|| swap_error(format!("{party} inputs do not cover {amount} of asset {asset}")))?;
+    Ok(())
+}
+
+/// Builds unsigned PSET swapping `asset_to_buy` of the taker for `asset_to_sell` of the maker.
+///
+/// Order price is in `asset_to_buy` units per contract of `asset_to_sell`.
+pub fn build_swap_pset(
+    order: &OrderPlaceEventTags,
+    params: &SwapProposalParams,
+) -> crate::error::Result<PartiallySignedTransaction> {
+    let asset_to_sell = order_asset("asset_to_sell", &order.asset_to_sell)?;
+    let asset_to_buy = order_asset("asset_to_buy", &order.asset_to_buy)?;
+    if let Some(offered) = order.amount.filter(|offered| params.sell_amount > *offered) {
+        return Err(swap_error(format!(
+            "swap buys {} of asset {asset_to_sell}, order offers at most {offered}",
+            params.sell_amount
+        )));
+    }
+    let buy_amount = params
+        .sell_amount
+        .checked_mul(order.price)
+        .ok_or_else(|| swap_error("amount overflow"))?;
+
+    let mut maker_change = sum_by_asset(params.maker_inputs.iter().map(|x| &x.utxo))?;
+    let mut taker_change = sum_by_asset(params.taker_inputs.iter().map(|x| &x.utxo))?;
+
+    spend(&mut maker_change, asset_to_sell, params.sell_amount, "maker")?;
+    spend(&mut taker_change, asset_to_buy, buy_amount, "taker")?;
+    spend(&mut taker_change, params.fee_asset, params.fee, "taker")?;
+
+    let mut pset = PartiallySignedTransaction::new_v2();
+    for input in params.maker_inputs.iter().chain(&params.taker_inputs) {
+        let mut pset_input = Input::from_prevout(input.outpoint);
+        pset_input.witness_utxo = Some(input.utxo.clone());
+        pset.add_input(pset_input);
+    }
+
+    pset.add_output(Output::new_explicit(
+        params.taker_script.clone(),
+        params.sell_amount,
+        asset_to_sell,
+        None,
+    ));
+    pset.add_output(Output::new_explicit(
+        params.maker_script.clone(),
+        buy_amount,
+        asset_to_buy,
+        None,
+    ));
+    for (change, script) in [
+        (maker_change, &params.maker_script),
+        (taker_change, &params.taker_script),
+    ] {
+        let mut change = change.into_iter().filter(|(_, x)| *x > 0).collect::<Vec<_>>();
+        change.sort();
+        for (asset, amount) in change {
+            pset.add_output(Output::new_explicit(script.clone(), amount, asset, None));
+        }
+    }
+    pset.add_output(Output::new_explicit(Script::new(), params.fee, params.fee_asset, None));
+
+    Ok(pset)
+}
+
+/// Validates swap PSET from the maker perspective.
+///
+/// Maker inputs and outputs are recognized by `maker_script`. Maker must receive at least
+/// `price` of `asset_to_buy` per sold contract, must not sell more than the order `amount`
+/// and must not lose any other asset.
+pub fn validate_swap_pset(
+    pset: &PartiallySignedTransaction,
+    order: &OrderPlaceEventTags,
+    maker_script: &Script,
+) -> crate::error::Result<SwapSummary> {
+    let asset_to_sell = order_asset("asset_to_sell", &order.asset_to_sell)?;
+    let asset_to_buy = order_asset("asset_to_buy", &order.asset_to_buy)?;
+
+    let mut spent_utxos = Vec::with_capacity(pset.inputs().len());
+    for (index, input) in pset.inputs().iter().enumerate() {
+        let utxo = input
+            .witness_utxo
+            .as_ref()
+            .ok_or_else(|| swap_error(format!("input {index} has no witness utxo")))?;
+        spent_utxos.push(utxo);
+    }
+    let spent = sum_by_asset(spent_utxos.into_iter().filter(|x| &x.script_pubkey == maker_script))?;
+    if spent.is_empty() {
+        return Err(swap_error("PSET does not spend maker inputs"));
+    }
+
+    let mut received: HashMap<AssetId, u64> = HashMap::new();
+    let mut fee = 0;
+    for output in pset.outputs() {
+        let (Some(asset), Some(amount)) = (output.asset, output.amount) else {
+            return Err(swap_error("confidential outputs are not supported"));
+        };
+        if output.script_pubkey.is_empty() {
+            fee = amount.saturating_add(fee);
+        } else if &output.script_pubkey == maker_script {
+            let sum = received.entry(asset).or_default();
+            *sum = sum.saturating_add(amount);
+        }
+    }
+
+    for (asset, spent_amount) in &spent {
+        let received_amount = received.get(asset).copied().unwrap_or_default();
+        if *asset != asset_to_sell && received_amount < *spent_amount {
+            return Err(swap_error(format!(
+                "maker loses {} of asset {asset} which is not sold by the order",
+                spent_amount - received_amount
+            )));
+        }
+    }
+
+    let sold_amount = spent
+        .get(&asset_to_sell)
+        .copied()
+        .unwrap_or_default()
+        .saturating_sub(received.get(&asset_to_sell).copied().unwrap_or_default());
+    let received_amount = received
+        .get(&asset_to_buy)
+        .copied()
+        .unwrap_or_default()
+        .saturating_sub(spent.get(&asset_to_buy).copied().unwrap_or_default());
+
+    if sold_amount == 0 {
+        return Err(swap_error("PSET does not sell any asset of the order"));
+    }
+    if let Some(offered) = order.amount.filter(|offered| sold_amount > *offered) {
+        return Err(swap_error(format!(
+            "maker sells {sold_amount} of asset {asset_to_sell}, order offers at most {offered}"
+        )));
+    }
+    let expected_amount = sold_amount
+        .checked_mul(order.price)
+        .ok_or_else(|| swap_error("amount overflow"))?;
+    if received_amount < expected_amount {
+        return Err(swap_error(format!(
+            "maker receives {received_amount} of asset {asset_to_buy}, expected at least {expected_amount}"
+        )));
+    }
+
+    Ok(SwapSummary {
+        sold_amount,
+        received_amount,
+        fee,
+    })
+}
+
+/// Finalizes signed inputs and extracts the swap transaction.
+///
+/// Inputs are expected to be already finalized or signed with a single P2WPKH signature.
+pub fn finalize_swap_pset(mut pset: PartiallySignedTransaction) -> crate::error::Result<Transaction> {
+    for (index, input) in pset.inputs_mut().iter_mut().enumerate() {
+        if input.final_script_witness.is_some() {
+            continue;
+        }
+
+        let is_p2wpkh = input
+            .witness_utxo
+            .as_ref()
+            .is_some_and(|x| x.script_pubkey.is_v0_p2wpkh());
+        match input.partial_sigs.pop_first() {
+            Some((pubkey, signature)) if is_p2wpkh && input.partial_sigs.is_empty() => {
+                input.final_script_witness = Some(vec![signature, pubkey.to_bytes()]);
+            }
+            _ => return Err(swap_error(format!("input {index} is not signed"))),
+        }
+    }
+
+    Ok(pset.extract_tx()?)
+}
@@ -0,0 +1,19 @@
+use crate::error::NostrRelayError;
+
+use nostr::{Event, TagKind};
+
+/// Value at `index` of the first custom tag named `tag`, index `0` is the tag name itself.
+pub(crate) fn tag_value<'a>(event: &'a Event, tag: &str, index: usize) -> Option<&'a str> {
+    event
+        .tags
+        .find(TagKind::custom(tag))
+        .and_then(|x| x.as_slice().get(index))
+        .map(String::as_str)
+}
+
+pub(crate) fn required_tag_value<'a>(event: &'a Event, tag: &str, index: usize) -> crate::error::Result<&'a str> {
+    tag_value(event, tag, index).ok_or_else(|| NostrRelayError::MissingOrderTag {
+        tag: tag.to_string(),
+        index,
+    })
+}
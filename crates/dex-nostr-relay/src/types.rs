@@ -14,12 +14,22 @@ pub trait CustomKind {
 
 pub const BLOCKSTREAM_MAKER_CONTENT: &str = "Liquid order [Maker]";
 pub const BLOCKSTREAM_TAKER_CONTENT: &str = "Liquid order [Taker]";
+pub const BLOCKSTREAM_MAKER_ACCEPT_CONTENT: &str = "Liquid order [Maker accept]";
+pub const BLOCKSTREAM_QUOTE_REQUEST_CONTENT: &str = "Liquid order [Quote request]";
+pub const BLOCKSTREAM_QUOTE_CONTENT: &str = "Liquid order [Quote]";
+pub const BLOCKSTREAM_SETTLEMENT_CONTENT: &str = "Liquid order [Settlement]";
+pub const BLOCKSTREAM_ORACLE_ATTESTATION_CONTENT: &str = "Liquid price [Oracle attestation]";
 
 // TODO: move to the config
 pub const MAKER_EXPIRATION_TIME: u64 = 60;
 
 pub struct MakerOrderKind;
 pub struct TakerOrderKind;
+pub struct MakerAcceptKind;
+pub struct OracleAttestationKind;
+pub struct SettlementKind;
+pub struct QuoteRequestKind;
+pub struct QuoteKind;
 
 impl CustomKind for MakerOrderKind {
     const ORDER_KIND_NUMBER: u16 = 9901;
@@ -28,3 +38,23 @@ impl CustomKind for MakerOrderKind {
 impl CustomKind for TakerOrderKind {
     const ORDER_KIND_NUMBER: u16 = 9902;
 }
+
+impl CustomKind for MakerAcceptKind {
+    const ORDER_KIND_NUMBER: u16 = 9903;
+}
+
+impl CustomKind for OracleAttestationKind {
+    const ORDER_KIND_NUMBER: u16 = 9904;
+}
+
+impl CustomKind for SettlementKind {
+    const ORDER_KIND_NUMBER: u16 = 9905;
+}
+
+impl CustomKind for QuoteRequestKind {
+    const ORDER_KIND_NUMBER: u16 = 9906;
+}
+
+impl CustomKind for QuoteKind {
+    const ORDER_KIND_NUMBER: u16 = 9907;
+}
@@ -0,0 +1,67 @@
+mod tests {
+    use nostr::nips::nip09::EventDeletionRequest;
+    use nostr::{EventBuilder, JsonUtil, Keys, Timestamp};
+
+    use dex_nostr_relay::archive::{prepare_archive, read_ndjson, write_ndjson};
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind, TakerOrderKind};
+
+    #[test]
+    fn test_archive_round_trip() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let order = EventBuilder::new(MakerOrderKind::get_kind(), "")
+            .custom_created_at(Timestamp::from(100))
+            .sign_with_keys(&keys)?;
+        let reply = EventBuilder::new(TakerOrderKind::get_kind(), "")
+            .custom_created_at(Timestamp::from(200))
+            .sign_with_keys(&keys)?;
+        let deletion = EventBuilder::delete(EventDeletionRequest::new().id(order.id))
+            .custom_created_at(Timestamp::from(300))
+            .sign_with_keys(&keys)?;
+        let unrelated_deletion = EventBuilder::delete(EventDeletionRequest::new().id(deletion.id))
+            .custom_created_at(Timestamp::from(50))
+            .sign_with_keys(&keys)?;
+
+        let foreign_deletion = EventBuilder::delete(EventDeletionRequest::new().id(reply.id))
+            .custom_created_at(Timestamp::from(400))
+            .sign_with_keys(&Keys::generate())?;
+
+        let archive = prepare_archive([
+            deletion.clone(),
+            reply.clone(),
+            unrelated_deletion,
+            foreign_deletion,
+            order.clone(),
+        ]);
+        let ids = archive.iter().map(|x| x.id).collect::<Vec<_>>();
+        assert_eq!(ids, vec![order.id, reply.id, deletion.id]);
+
+        let mut buffer = Vec::new();
+        assert_eq!(write_ndjson(&archive, &mut buffer)?, 3);
+        assert_eq!(read_ndjson(buffer.as_slice())?, archive);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_archive_is_rejected() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let order = EventBuilder::new(MakerOrderKind::get_kind(), "").sign_with_keys(&keys)?;
+        let note = EventBuilder::text_note("").sign_with_keys(&keys)?;
+        let forged = order.as_json().replace("\"content\":\"\"", "\"content\":\"forged\"");
+
+        for (archive, line) in [
+            (format!("{}\n\n{}\n", order.as_json(), note.as_json()), 3),
+            (format!("{forged}\n"), 1),
+            ("not json\n".to_string(), 1),
+        ] {
+            let res = read_ndjson(archive.as_bytes());
+            assert!(
+                matches!(res, Err(NostrRelayError::InvalidArchive { line: x, .. }) if x == line),
+                "{res:?}"
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+mod tests {
+    use dex_nostr_relay::batch::{LadderSpec, SizeDistribution};
+    use dex_nostr_relay::error::NostrRelayError;
+
+    fn spec(orders: usize, total_amount: u64, distribution: SizeDistribution) -> LadderSpec {
+        LadderSpec {
+            asset_to_sell: "11".repeat(32),
+            asset_to_buy: "22".repeat(32),
+            min_price: 100,
+            max_price: 200,
+            orders,
+            total_amount,
+            distribution,
+            compiler_name: "simplicity-v1.2.3".to_string(),
+            compiler_build_hash: "ab12cd".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_ladder_prices_and_sizes() -> anyhow::Result<()> {
+        let orders = spec(3, 1_000, SizeDistribution::Uniform).generate()?;
+        let prices = orders.iter().map(|x| x.price).collect::<Vec<_>>();
+        let amounts = orders.iter().map(|x| x.amount).collect::<Vec<_>>();
+        assert_eq!(prices, vec![100, 150, 200]);
+        assert_eq!(amounts, vec![Some(333), Some(333), Some(334)]);
+
+        let orders = spec(4, 1_000, SizeDistribution::Decreasing).generate()?;
+        let amounts = orders.iter().map(|x| x.amount.unwrap_or_default()).collect::<Vec<_>>();
+        assert_eq!(amounts, vec![400, 300, 200, 100]);
+        assert_eq!(orders.last().map(|x| x.price), Some(200));
+
+        let single = spec(1, 10, SizeDistribution::Increasing).generate()?;
+        assert_eq!(single.len(), 1);
+        assert_eq!(single[0].price, 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_ladder_is_rejected() {
+        for invalid in [
+            spec(0, 1_000, SizeDistribution::Uniform),
+            spec(5, 3, SizeDistribution::Uniform),
+            LadderSpec {
+                max_price: 50,
+                ..spec(2, 1_000, SizeDistribution::Uniform)
+            },
+            LadderSpec {
+                compiler_name: String::new(),
+                ..spec(2, 1_000, SizeDistribution::Uniform)
+            },
+        ] {
+            assert!(matches!(
+                invalid.generate(),
+                Err(NostrRelayError::InvalidLadderSpec { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+mod tests {
+    use std::borrow::Cow;
+    use std::time::Duration;
+
+    use nostr::{EventBuilder, Keys, Tag, TagKind};
+
+    use dex_nostr_relay::compiler_provenance::{CompilerAllowList, CompilerStatus};
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::relay_client::ClientConfig;
+    use dex_nostr_relay::relay_processor::{OrderPlaceEventTags, RelayProcessor};
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind};
+
+    const ALLOW_LIST: &str = r#"
+        [[trusted]]
+        name = "simplicity-v1.2.3"
+        build_hash = "ab12cd"
+
+        [[trusted]]
+        name = "simplicity-v1.2.4"
+        build_hash = "ef34"
+
+        [[blacklisted]]
+        name = "simplicity-v1.2.4"
+    "#;
+
+    #[test]
+    fn test_compiler_allow_list_status() -> anyhow::Result<()> {
+        let allow_list = CompilerAllowList::from_toml_str(ALLOW_LIST)?;
+
+        assert_eq!(
+            allow_list.status("simplicity-v1.2.3", "AB12CD"),
+            CompilerStatus::Trusted
+        );
+        assert_eq!(allow_list.status("simplicity-v1.2.3", "ffff"), CompilerStatus::Unknown);
+        assert_eq!(
+            allow_list.status("simplicity-v1.2.4", "ef34"),
+            CompilerStatus::Blacklisted
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_order_compiler_tag_validation() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let order = |build_hash: &str| {
+            EventBuilder::new(MakerOrderKind::get_kind(), "")
+                .tags([
+                    Tag::custom(
+                        TagKind::Custom(Cow::from("compiler")),
+                        ["simplicity-v1.2.3", build_hash],
+                    ),
+                    Tag::custom(TagKind::Custom(Cow::from("asset_to_buy")), ["buy"]),
+                    Tag::custom(TagKind::Custom(Cow::from("asset_to_sell")), ["sell"]),
+                    Tag::custom(TagKind::Custom(Cow::from("price")), ["1000"]),
+                ])
+                .sign_with_keys(&keys)
+        };
+
+        let tags = OrderPlaceEventTags::try_from(&order("ab12cd")?)?;
+        assert_eq!(tags.compiler_name, "simplicity-v1.2.3");
+        assert_eq!(tags.compiler_build_hash, "ab12cd");
+        assert_eq!(tags.price, 1000);
+
+        assert!(matches!(
+            OrderPlaceEventTags::try_from(&order("not-a-hash")?),
+            Err(NostrRelayError::InvalidOrderTag { tag, .. }) if tag == "compiler"
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_allow_list_entries_are_skipped() -> anyhow::Result<()> {
+        let allow_list = CompilerAllowList::from_toml_str(
+            r#"
+            [[trusted]]
+            name = "<compiler_version>"
+            build_hash = "<deterministic_build_hash>"
+
+            [[trusted]]
+            name = "simplicity-v1.2.3"
+            build_hash = "ab12cd"
+
+            [[blacklisted]]
+            name = ""
+            "#,
+        )?;
+
+        assert_eq!(allow_list.trusted.len(), 1);
+        assert!(allow_list.blacklisted.is_empty());
+        assert_eq!(
+            allow_list.status("simplicity-v1.2.3", "ab12cd"),
+            CompilerStatus::Trusted
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_placed_order_passes_compiler_validation() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let relay_processor =
+            RelayProcessor::try_from_config(Vec::<String>::new(), None::<Keys>, ClientConfig::default()).await?;
+        let tags = OrderPlaceEventTags {
+            asset_to_sell: "11".repeat(32),
+            asset_to_buy: "22".repeat(32),
+            price: 1000,
+            expiry: 1_735_689_600,
+            compiler_name: "simplicity-v1.2.3".to_string(),
+            compiler_build_hash: "ab12cd".to_string(),
+            ..Default::default()
+        };
+
+        let unsigned = relay_processor
+            .build_order(tags.clone(), maker.public_key, Duration::from_secs(60))
+            .await?;
+        let order = OrderPlaceEventTags::try_from(&unsigned.sign_with_keys(&maker)?)?;
+        assert_eq!(order.expiry, 1_735_689_600);
+
+        let res = relay_processor
+            .build_order(
+                OrderPlaceEventTags {
+                    compiler_name: String::new(),
+                    ..tags
+                },
+                maker.public_key,
+                Duration::from_secs(60),
+            )
+            .await;
+        assert!(matches!(res, Err(NostrRelayError::InvalidOrderTag { tag, .. }) if tag == "compiler"));
+
+        Ok(())
+    }
+}
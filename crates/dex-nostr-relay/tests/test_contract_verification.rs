@@ -0,0 +1,74 @@
+mod tests {
+    use simplicity::Cmr;
+    use simplicity::elements::{Address, AddressParams};
+
+    use dex_nostr_relay::contract_verification::{contract_script_pubkey, decode_program, verify_program};
+    use dex_nostr_relay::error::NostrRelayError;
+
+    /// `comp (injl unit) jet_verify`
+    const VERIFY_PROGRAM: &str = "wSRgEA==";
+    const VERIFY_PROGRAM_CMR: &str = "fc5770fe4305f810434a42103c363f872190515a916e590e125241104340978f";
+
+    #[test]
+    fn test_program_matches_advertised_cmr_and_address() -> anyhow::Result<()> {
+        let cmr = decode_program(VERIFY_PROGRAM)?.cmr();
+        let address =
+            Address::from_script(&contract_script_pubkey(cmr), None, &AddressParams::LIQUID).expect("p2tr script");
+
+        let summary = verify_program(VERIFY_PROGRAM, Some(VERIFY_PROGRAM_CMR), Some(&address.to_string()))?;
+        assert_eq!(summary.cmr.to_string(), VERIFY_PROGRAM_CMR);
+        assert_eq!(summary.source_type, "1");
+        assert_eq!(summary.target_type, "1");
+        assert!(summary.jets.contains("verify"));
+        assert_eq!(summary.cmr_matches, Some(true));
+        assert_eq!(summary.address_matches, Some(true));
+        assert!(summary.is_verified());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_with_mismatched_cmr_is_not_verified() -> anyhow::Result<()> {
+        let summary = verify_program(VERIFY_PROGRAM, Some(&"00".repeat(32)), None)?;
+        assert_eq!(summary.cmr_matches, Some(false));
+        assert_eq!(summary.address_matches, None);
+        assert!(!summary.is_verified());
+
+        let summary = verify_program(VERIFY_PROGRAM, None, None)?;
+        assert!(!summary.is_verified());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_program_matching_only_advertised_cmr_is_not_verified() -> anyhow::Result<()> {
+        let summary = verify_program(VERIFY_PROGRAM, Some(VERIFY_PROGRAM_CMR), None)?;
+        assert_eq!(summary.cmr_matches, Some(true));
+        assert_eq!(summary.address_matches, None);
+        assert!(!summary.is_verified());
+
+        let other_address = Address::from_script(
+            &contract_script_pubkey(Cmr::from_byte_array([0x11; 32])),
+            None,
+            &AddressParams::LIQUID,
+        )
+        .expect("p2tr script");
+        let summary = verify_program(
+            VERIFY_PROGRAM,
+            Some(VERIFY_PROGRAM_CMR),
+            Some(&other_address.to_string()),
+        )?;
+        assert_eq!(summary.address_matches, Some(false));
+        assert!(!summary.is_verified());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_program_is_rejected() {
+        assert!(matches!(
+            decode_program("not a program"),
+            Err(NostrRelayError::InvalidContractProgram { .. })
+        ));
+    }
+}
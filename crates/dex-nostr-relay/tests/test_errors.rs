@@ -0,0 +1,96 @@
+mod tests {
+    use std::error::Error;
+    use std::time::Duration;
+
+    use nostr::{Keys, RelayUrl};
+    use nostr_sdk::pool::relay;
+
+    use dex_nostr_relay::error::{NostrRelayError, RelayFailure, SourceError};
+    use dex_nostr_relay::maker_registry::FileMakerRegistry;
+    use dex_nostr_relay::relay_client::{ClientConfig, RelayClient};
+    use dex_nostr_relay::relay_health::RelayOperation;
+
+    fn rejected(reasons: &[&str]) -> anyhow::Result<NostrRelayError> {
+        let failures = reasons
+            .iter()
+            .map(|reason| {
+                Ok(RelayFailure {
+                    url: RelayUrl::parse("wss://relay.example.com")?,
+                    reason: reason.to_string(),
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok(NostrRelayError::AllRelaysRejected {
+            operation: RelayOperation::Publish,
+            failures,
+        })
+    }
+
+    #[test]
+    fn test_retry_classification() -> anyhow::Result<()> {
+        let timeout = NostrRelayError::Timeout {
+            operation: RelayOperation::Fetch,
+            timeout: Duration::from_secs(10),
+        };
+        assert!(timeout.is_retryable());
+        assert_eq!(timeout.to_string(), "Relay fetch timed out after 10s");
+        assert!(
+            NostrRelayError::NoRelaysConnected {
+                operation: RelayOperation::Publish
+            }
+            .is_retryable()
+        );
+        assert!(
+            NostrRelayError::NostrClientFailure(nostr_sdk::client::Error::Relay(relay::Error::NotConnected))
+                .is_retryable()
+        );
+
+        assert!(rejected(&["rate-limited: slow down"])?.is_retryable());
+        assert!(rejected(&["invalid: bad signature", "connection closed"])?.is_retryable());
+        assert!(!rejected(&["invalid: bad signature", "pow: difficulty 20 is required"])?.is_retryable());
+
+        assert!(!NostrRelayError::MissingSigner.is_retryable());
+        assert!(
+            !NostrRelayError::InvalidOrderTag {
+                tag: "price".to_string(),
+                err_msg: String::new(),
+            }
+            .is_retryable()
+        );
+        assert!(
+            !NostrRelayError::MissingOrderTag {
+                tag: "price".to_string(),
+                index: 1,
+            }
+            .is_retryable()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_relay_url_keeps_source() {
+        let res = RelayClient::connect(["not a relay url"], None::<Keys>, ClientConfig::default()).await;
+
+        let Err(err @ NostrRelayError::InvalidRelayUrl { .. }) = res else {
+            panic!("expected invalid relay url error");
+        };
+        assert!(err.source().is_some());
+        assert!(!err.is_retryable());
+    }
+
+    #[test]
+    fn test_missing_maker_registry_keeps_io_source() {
+        let path = std::env::temp_dir().join("dex-makers-missing.txt");
+
+        let res = FileMakerRegistry::from_file(&path);
+        assert!(matches!(
+            res,
+            Err(NostrRelayError::FailedToLoadMakerRegistry {
+                source: SourceError::Io(ref err),
+                ..
+            }) if err.kind() == std::io::ErrorKind::NotFound
+        ));
+    }
+}
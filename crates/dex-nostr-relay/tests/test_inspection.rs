@@ -0,0 +1,70 @@
+mod tests {
+    use std::borrow::Cow;
+
+    use nostr::{EventBuilder, JsonUtil, Keys, Kind, Tag, TagKind, Timestamp};
+
+    use dex_nostr_relay::inspection::{DecodedEvent, DexEventKind, inspect_event};
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind, TakerOrderKind};
+
+    fn order_builder(asset_to_sell: &str) -> EventBuilder {
+        EventBuilder::new(MakerOrderKind::get_kind(), "").tags([
+            Tag::expiration(Timestamp::from(1_000)),
+            Tag::custom(TagKind::Custom(Cow::from("compiler")), ["simplicity-v1", "abcd"]),
+            Tag::custom(TagKind::Custom(Cow::from("asset_to_sell")), [asset_to_sell.to_string()]),
+            Tag::custom(TagKind::Custom(Cow::from("asset_to_buy")), ["22".repeat(32)]),
+            Tag::custom(TagKind::Custom(Cow::from("price")), ["100"]),
+        ])
+    }
+
+    #[test]
+    fn test_valid_order_is_decoded() -> anyhow::Result<()> {
+        let event = order_builder(&"11".repeat(32)).sign_with_keys(&Keys::generate())?;
+
+        let inspection = inspect_event(&event, Timestamp::from(500));
+        assert_eq!(inspection.kind, DexEventKind::MakerOrder);
+        assert!(inspection.is_valid(), "{inspection}");
+        assert!(matches!(inspection.decoded, Some(DecodedEvent::Order(tags)) if tags.price == 100));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_protocol_violations_are_listed() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let order = order_builder("btc").sign_with_keys(&keys)?;
+        let inspection = inspect_event(&order, Timestamp::from(2_000));
+        assert_eq!(inspection.violations.len(), 2, "{inspection}");
+        assert!(inspection.violations.iter().any(|x| x.contains("expired")));
+        assert!(inspection.violations.iter().any(|x| x.contains("asset_to_sell")));
+
+        let endless = EventBuilder::new(MakerOrderKind::get_kind(), "")
+            .tags([
+                Tag::custom(TagKind::Custom(Cow::from("compiler")), ["simplicity-v1", "abcd"]),
+                Tag::custom(TagKind::Custom(Cow::from("asset_to_sell")), ["11".repeat(32)]),
+                Tag::custom(TagKind::Custom(Cow::from("asset_to_buy")), ["22".repeat(32)]),
+                Tag::custom(TagKind::Custom(Cow::from("price")), ["100"]),
+            ])
+            .sign_with_keys(&keys)?;
+        let inspection = inspect_event(&endless, Timestamp::from(500));
+        assert_eq!(inspection.violations.len(), 1, "{inspection}");
+        assert!(inspection.violations[0].contains("expiration"));
+
+        let forged = order.as_json().replace("\"100\"", "\"1\"");
+        let forged = nostr::Event::from_json(forged)?;
+        let inspection = inspect_event(&forged, Timestamp::from(500));
+        assert!(!inspection.id_valid);
+
+        let reply = EventBuilder::new(TakerOrderKind::get_kind(), "").sign_with_keys(&keys)?;
+        let inspection = inspect_event(&reply, Timestamp::from(500));
+        assert_eq!(inspection.kind, DexEventKind::TakerReply);
+        assert_eq!(inspection.violations.len(), 3, "{inspection}");
+
+        let note = EventBuilder::new(Kind::TextNote, "").sign_with_keys(&keys)?;
+        assert_eq!(
+            inspect_event(&note, Timestamp::from(500)).kind,
+            DexEventKind::Unknown(1)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+mod tests {
+    use std::time::Duration;
+
+    use dex_nostr_relay::error::{NostrRelayError, SourceError};
+    use dex_nostr_relay::keeper::{KeeperSpec, OraclePriceSource};
+
+    #[test]
+    fn test_keeper_spec_parsing() -> anyhow::Result<()> {
+        let spec = KeeperSpec::from_toml_str(
+            r#"
+            refresh_margin_secs = 20
+
+            [[orders]]
+            asset_to_sell = "aa"
+            asset_to_buy = "bb"
+            price = 1000
+            amount = 50
+            compiler_name = "simplicity-v1.2.3"
+            compiler_build_hash = "ab12cd"
+
+            [[orders]]
+            asset_to_sell = "aa"
+            asset_to_buy = "cc"
+            price = 7
+            reprice = true
+            compiler_name = "simplicity-v1.2.3"
+            compiler_build_hash = "ab12cd"
+            "#,
+        )?;
+        assert_eq!(spec.refresh_interval(), Duration::from_secs(40));
+        assert!(!spec.publish_before_cancel);
+        assert_eq!(spec.orders.len(), 2);
+        assert!(spec.orders[1].reprice);
+
+        let order = spec.orders[0].to_order_tags(1_100);
+        assert_eq!(order.price, 1_100);
+        assert_eq!(order.amount, Some(50));
+        assert_eq!(order.asset_to_buy, "bb");
+
+        let default_margin = KeeperSpec::from_toml_str("orders = []")?;
+        assert_eq!(default_margin.refresh_interval(), Duration::from_secs(45));
+
+        assert!(matches!(
+            KeeperSpec::from_toml_str("[[orders]]\nprice = 1"),
+            Err(NostrRelayError::FailedToLoadKeeperSpec {
+                source: SourceError::Toml(_),
+                ..
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_oracle_price_spread() {
+        let source = OraclePriceSource {
+            tolerance: Duration::from_secs(60),
+            spread_bps: 250,
+        };
+        assert_eq!(source.apply_spread(1_000), 1_025);
+    }
+}
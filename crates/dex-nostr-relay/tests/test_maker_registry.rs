@@ -0,0 +1,31 @@
+mod tests {
+    use nostr::{Keys, ToBech32};
+
+    use dex_nostr_relay::maker_registry::{FileMakerRegistry, MakerRegistry};
+
+    #[tokio::test]
+    async fn test_file_maker_registry() -> anyhow::Result<()> {
+        let registered_maker = Keys::generate().public_key;
+        let registered_hex_maker = Keys::generate().public_key;
+        let unknown_maker = Keys::generate().public_key;
+
+        let path = std::env::temp_dir().join(format!("dex-makers-{}.txt", registered_maker.to_hex()));
+        std::fs::write(
+            &path,
+            format!(
+                "# registered makers\n\n{}\n<registered_maker_pubkey>\n{}\n",
+                registered_maker.to_bech32()?,
+                registered_hex_maker.to_hex()
+            ),
+        )?;
+        let registry = FileMakerRegistry::from_file(&path);
+        std::fs::remove_file(&path)?;
+        let registry = registry?;
+
+        assert!(registry.is_registered(&registered_maker).await?);
+        assert!(registry.is_registered(&registered_hex_maker).await?);
+        assert!(!registry.is_registered(&unknown_maker).await?);
+
+        Ok(())
+    }
+}
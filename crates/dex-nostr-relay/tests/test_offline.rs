@@ -0,0 +1,66 @@
+mod tests {
+    use std::borrow::Cow;
+
+    use nostr::{EventBuilder, JsonUtil, Keys, Tag, TagKind, Timestamp, UnsignedEvent};
+
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::offline::{parse_signed_event, parse_unsigned_event, sign_unsigned_event};
+    use dex_nostr_relay::relay_processor::OrderPlaceEventTags;
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind};
+
+    fn unsigned_order(maker: &Keys) -> UnsignedEvent {
+        let mut unsigned = EventBuilder::new(MakerOrderKind::get_kind(), "")
+            .tags([
+                Tag::expiration(Timestamp::now() + 3_600),
+                Tag::custom(TagKind::Custom(Cow::from("compiler")), ["simplicity-v1", "abcd"]),
+                Tag::custom(TagKind::Custom(Cow::from("asset_to_buy")), ["22".repeat(32)]),
+                Tag::custom(TagKind::Custom(Cow::from("asset_to_sell")), ["11".repeat(32)]),
+                Tag::custom(TagKind::Custom(Cow::from("price")), ["100"]),
+            ])
+            .build(maker.public_key);
+        unsigned.ensure_id();
+        unsigned
+    }
+
+    #[test]
+    fn test_offline_order_signing_round_trip() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let exported = unsigned_order(&maker).as_json();
+
+        let event = sign_unsigned_event(parse_unsigned_event(&exported)?, &maker)?;
+        assert_eq!(event.pubkey, maker.public_key);
+
+        let published = parse_signed_event(&event.as_json())?;
+        assert_eq!(published.id, event.id);
+        assert_eq!(OrderPlaceEventTags::try_from(&published)?.price, 100);
+
+        assert!(matches!(
+            sign_unsigned_event(parse_unsigned_event(&exported)?, &Keys::generate()),
+            Err(NostrRelayError::InvalidOfflineEvent { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tampered_events_are_rejected() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let unsigned = unsigned_order(&maker);
+
+        let mut tampered = unsigned.clone();
+        tampered.content = "changed".to_string();
+        assert!(matches!(
+            parse_unsigned_event(&tampered.as_json()),
+            Err(NostrRelayError::InvalidOfflineEvent { .. })
+        ));
+
+        let event = sign_unsigned_event(unsigned, &maker)?;
+        let forged = event.as_json().replace("\"100\"", "\"1\"");
+        assert!(parse_signed_event(&forged).is_err());
+
+        let note = EventBuilder::text_note("").sign_with_keys(&maker)?;
+        assert!(parse_signed_event(&note.as_json()).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+mod tests {
+    use nostr::{EventBuilder, Keys, Timestamp};
+
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::option_terms::{OptionStyle, OptionTerms, OptionTermsFilter, OptionType, Settlement};
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind};
+
+    fn call_terms() -> OptionTerms {
+        OptionTerms {
+            option_type: OptionType::Call,
+            style: OptionStyle::European,
+            strike_price: 1_000_000,
+            settlement: Settlement::Time(1_735_689_600),
+            collateral_asset: "11".repeat(32),
+            settlement_asset: "22".repeat(32),
+        }
+    }
+
+    #[test]
+    fn test_option_terms_tags_round_trip() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let terms = call_terms();
+
+        let event = EventBuilder::new(MakerOrderKind::get_kind(), "")
+            .tags(terms.to_tags())
+            .sign_with_keys(&keys)?;
+        assert_eq!(OptionTerms::from_event(&event)?, Some(terms));
+
+        let event = EventBuilder::new(MakerOrderKind::get_kind(), "").sign_with_keys(&keys)?;
+        assert_eq!(OptionTerms::from_event(&event)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_option_terms_validation() {
+        let terms = call_terms();
+        assert!(terms.validate().is_ok());
+        assert!(matches!(
+            terms.validate_for_placement(Timestamp::from(1_735_689_600)),
+            Err(NostrRelayError::InvalidOrderTag { tag, .. }) if tag == "settlement"
+        ));
+
+        let terms = OptionTerms {
+            settlement_asset: terms.collateral_asset.clone(),
+            ..call_terms()
+        };
+        assert!(matches!(
+            terms.validate(),
+            Err(NostrRelayError::InvalidOrderTag { tag, .. }) if tag == "settlement_asset"
+        ));
+    }
+
+    #[test]
+    fn test_option_terms_filter() {
+        let terms = call_terms();
+
+        assert!(OptionTermsFilter::default().matches(&terms));
+        assert!(!OptionTermsFilter::default().has_criteria());
+        assert!(
+            OptionTermsFilter {
+                settlement_asset: Some("22".repeat(32)),
+                ..Default::default()
+            }
+            .has_criteria()
+        );
+        assert!(
+            OptionTermsFilter {
+                option_type: Some(OptionType::Call),
+                min_strike_price: Some(1_000_000),
+                settles_before: Some(1_735_689_600),
+                collateral_asset: Some("11".repeat(32)),
+                ..Default::default()
+            }
+            .matches(&terms)
+        );
+        assert!(
+            !OptionTermsFilter {
+                option_type: Some(OptionType::Put),
+                ..Default::default()
+            }
+            .matches(&terms)
+        );
+        assert!(
+            !OptionTermsFilter {
+                max_strike_price: Some(999_999),
+                ..Default::default()
+            }
+            .matches(&terms)
+        );
+    }
+}
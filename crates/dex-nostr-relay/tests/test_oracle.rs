@@ -0,0 +1,116 @@
+mod tests {
+    use std::time::Duration;
+
+    use nostr::{EventBuilder, Keys, Timestamp, ToBech32};
+
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::oracle::{AttestationQuery, OracleSet, PriceAttestation, PriceAttestationTags};
+    use dex_nostr_relay::types::{CustomKind, OracleAttestationKind};
+
+    fn attest(oracle: &Keys, price: u64, timestamp: u64) -> anyhow::Result<PriceAttestation> {
+        let tags = PriceAttestationTags {
+            base_asset: "11".repeat(32),
+            quote_asset: "22".repeat(32),
+            price,
+            timestamp: Timestamp::from(timestamp),
+        };
+        let event = EventBuilder::new(OracleAttestationKind::get_kind(), "")
+            .tags(tags.to_tags())
+            .sign_with_keys(oracle)?;
+
+        Ok(PriceAttestation::try_from(&event)?)
+    }
+
+    #[test]
+    fn test_attestation_parsing_and_query() -> anyhow::Result<()> {
+        let oracle = Keys::generate();
+        let attestation = attest(&oracle, 100, 1_000)?;
+        assert_eq!(attestation.oracle, oracle.public_key);
+        assert_eq!(attestation.tags.price, 100);
+
+        let query = AttestationQuery {
+            base_asset: "11".repeat(32),
+            quote_asset: "22".repeat(32),
+            timestamp: Timestamp::from(1_050),
+            tolerance: Duration::from_secs(60),
+        };
+        assert!(query.matches(&attestation));
+        assert!(!query.matches(&attest(&oracle, 100, 900)?));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_median_aggregation_with_threshold() -> anyhow::Result<()> {
+        let oracles = [Keys::generate(), Keys::generate(), Keys::generate()];
+        let untrusted = Keys::generate();
+        let oracle_set = OracleSet::new(oracles.iter().map(|x| x.public_key), 3);
+
+        let attestations = vec![
+            attest(&oracles[0], 100, 1_000)?,
+            attest(&oracles[0], 120, 1_010)?,
+            attest(&oracles[1], 90, 1_000)?,
+            attest(&oracles[2], 300, 1_000)?,
+            attest(&untrusted, 1, 1_000)?,
+        ];
+        let aggregated = oracle_set.aggregate(&attestations, Timestamp::from(1_010))?;
+        assert_eq!(aggregated.price, 120);
+        assert_eq!(aggregated.oracles.len(), 3);
+        assert!(!aggregated.oracles.contains(&untrusted.public_key));
+
+        assert!(matches!(
+            oracle_set.aggregate(&attestations[..3], Timestamp::from(1_010)),
+            Err(NostrRelayError::NotEnoughAttestations {
+                required: 3,
+                available: 2
+            })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_attestation_closest_to_query_is_aggregated() -> anyhow::Result<()> {
+        let oracle = Keys::generate();
+        let oracle_set = OracleSet::new([oracle.public_key], 1);
+
+        let attestations = vec![
+            attest(&oracle, 100, 990)?,
+            attest(&oracle, 110, 1_000)?,
+            attest(&oracle, 200, 1_030)?,
+        ];
+        let aggregated = oracle_set.aggregate(&attestations, Timestamp::from(1_005))?;
+        assert_eq!(aggregated.price, 110);
+        assert_eq!(aggregated.attestations, vec![attestations[1].event_id]);
+
+        let aggregated = oracle_set.aggregate(&attestations[1..], Timestamp::from(1_015))?;
+        assert_eq!(aggregated.price, 110);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_oracle_set_file_skips_malformed_keys() -> anyhow::Result<()> {
+        let oracle = Keys::generate().public_key;
+
+        let path = std::env::temp_dir().join(format!("dex-oracles-{}.txt", oracle.to_hex()));
+        std::fs::write(
+            &path,
+            format!(
+                "# oracles
+<oracle_pubkey>
+{}
+",
+                oracle.to_bech32()?
+            ),
+        )?;
+        let oracle_set = OracleSet::from_file(&path, 1);
+        std::fs::remove_file(&path)?;
+        let oracle_set = oracle_set?;
+
+        assert_eq!(oracle_set.oracles().len(), 1);
+        assert!(oracle_set.is_trusted(&oracle));
+
+        Ok(())
+    }
+}
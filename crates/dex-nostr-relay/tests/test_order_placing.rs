@@ -7,8 +7,9 @@ mod tests {
 
     use nostr::{EventId, Keys, ToBech32};
 
+    use dex_nostr_relay::query::QueryOptions;
     use dex_nostr_relay::relay_client::ClientConfig;
-    use dex_nostr_relay::relay_processor::{OrderPlaceEventTags, OrderReplyEventTags, RelayProcessor};
+    use dex_nostr_relay::relay_processor::{OrderFilter, OrderPlaceEventTags, OrderReplyEventTags, RelayProcessor};
     use dex_nostr_relay::types::{CustomKind, MakerOrderKind, TakerOrderKind};
 
     use tracing::{info, instrument};
@@ -18,25 +19,28 @@ mod tests {
     async fn test_wss_metadata() -> anyhow::Result<()> {
         let _guard = &*TEST_LOGGER;
         let key_maker = Keys::generate();
-        info!(
-            "=== Maker pubkey: {}, privatekey: {}",
-            key_maker.public_key.to_bech32()?,
-            key_maker.secret_key().to_bech32()?
-        );
+        info!("=== Maker pubkey: {}", key_maker.public_key.to_bech32()?);
         let relay_processor_maker = RelayProcessor::try_from_config(
             DEFAULT_RELAY_LIST,
             Some(key_maker.clone()),
             ClientConfig {
                 timeout: Duration::from_secs(DEFAULT_CLIENT_TIMEOUT),
+                ..Default::default()
             },
         )
         .await?;
 
         let placed_order_event_id = relay_processor_maker
-            .place_order(OrderPlaceEventTags::default())
+            .place_order(OrderPlaceEventTags {
+                compiler_name: "simplicity-v1.2.3".to_string(),
+                compiler_build_hash: "ab12cd".to_string(),
+                ..Default::default()
+            })
             .await?;
         info!("=== placed order event id: {}", placed_order_event_id);
-        let order = relay_processor_maker.get_events_by_id(placed_order_event_id).await?;
+        let order = relay_processor_maker
+            .get_events_by_id(placed_order_event_id, &QueryOptions::default())
+            .await?;
         info!("=== placed order: {:#?}", order);
         assert_eq!(order.len(), 1);
         assert_eq!(order.first().unwrap().kind, MakerOrderKind::get_kind());
@@ -47,14 +51,11 @@ mod tests {
             Some(key_taker.clone()),
             ClientConfig {
                 timeout: Duration::from_secs(DEFAULT_CLIENT_TIMEOUT),
+                ..Default::default()
             },
         )
         .await?;
-        info!(
-            "=== Taker pubkey: {}, privatekey: {}",
-            key_taker.public_key.to_bech32()?,
-            key_taker.secret_key().to_bech32()?
-        );
+        info!("=== Taker pubkey: {}", key_taker.public_key.to_bech32()?);
         let reply_event_id = relay_processor_taker
             .reply_order(
                 placed_order_event_id,
@@ -64,7 +65,9 @@ mod tests {
             .await?;
         info!("=== order reply event id: {}", reply_event_id);
 
-        let order_replies = relay_processor_maker.get_order_replies(placed_order_event_id).await?;
+        let order_replies = relay_processor_maker
+            .get_order_replies(placed_order_event_id, &QueryOptions::default())
+            .await?;
         info!(
             "=== order replies, amount: {}, orders: {:#?}",
             order_replies.len(),
@@ -73,7 +76,9 @@ mod tests {
         assert_eq!(order_replies.len(), 1);
         assert_eq!(order_replies.first().unwrap().kind, TakerOrderKind::get_kind());
 
-        let orders_listed = relay_processor_maker.list_orders().await?;
+        let orders_listed = relay_processor_maker
+            .list_orders(OrderFilter::default(), &QueryOptions::default())
+            .await?;
         info!(
             "=== orders listed, amount: {}, orders: {:#?}",
             orders_listed.len(),
@@ -0,0 +1,87 @@
+mod tests {
+    use std::collections::HashSet;
+
+    use nostr::{Event, EventBuilder, Filter, Keys, Timestamp};
+
+    use dex_nostr_relay::pagination::PageWindow;
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind};
+
+    /// Relay which caps every response at `cap` newest events within the window.
+    fn capped_relay(events: &[Event], filter: &Filter, cap: usize) -> Vec<Event> {
+        let mut matching = events
+            .iter()
+            .filter(|x| filter.until.is_none_or(|until| x.created_at <= until))
+            .cloned()
+            .collect::<Vec<Event>>();
+        matching.sort_by_key(|x| std::cmp::Reverse(x.created_at));
+        matching.truncate(cap.min(filter.limit.unwrap_or(usize::MAX)));
+        matching
+    }
+
+    fn walk(relays: &[Vec<Event>], filter: Filter, page_size: usize, cap: usize) -> Vec<Event> {
+        let mut window = PageWindow::new(filter, page_size);
+        let mut listed = Vec::new();
+        while let Some(filter) = window.filter() {
+            let pages = relays.iter().map(|events| capped_relay(events, &filter, cap));
+            listed.extend(window.advance(pages));
+        }
+        listed
+    }
+
+    #[test]
+    fn test_history_is_walked_across_capped_relays() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let events = [300, 300, 250, 200, 200, 100, 50]
+            .into_iter()
+            .enumerate()
+            .map(|(index, created_at)| {
+                EventBuilder::new(MakerOrderKind::get_kind(), index.to_string())
+                    .custom_created_at(Timestamp::from(created_at))
+                    .sign_with_keys(&keys)
+            })
+            .collect::<Result<Vec<Event>, _>>()?;
+        // Second relay misses the first events, so its capped windows reach deeper into history
+        let relays = vec![events.clone(), events[3..].to_vec()];
+
+        let listed = walk(&relays, Filter::new(), 10, 2);
+        assert_eq!(listed.len(), events.len());
+        assert_eq!(listed.iter().map(|x| x.id).collect::<HashSet<_>>().len(), events.len());
+        assert!(listed.windows(2).all(|x| x[0].created_at >= x[1].created_at));
+
+        let listed = walk(&relays, Filter::new().until(Timestamp::from(200)), 2, usize::MAX);
+        assert_eq!(listed.len(), 4);
+        assert!(listed.iter().all(|x| x.created_at <= Timestamp::from(200)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_walk_ends_when_relay_ignores_until() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let events = [300, 200, 100, 50]
+            .into_iter()
+            .map(|created_at| {
+                EventBuilder::new(MakerOrderKind::get_kind(), "")
+                    .custom_created_at(Timestamp::from(created_at))
+                    .sign_with_keys(&keys)
+            })
+            .collect::<Result<Vec<Event>, _>>()?;
+
+        let mut window = PageWindow::new(Filter::new(), 2);
+        let mut listed = Vec::new();
+        let mut windows = 0;
+        while let Some(filter) = window.filter() {
+            windows += 1;
+            assert!(windows <= events.len() * 2, "walk does not end");
+            let pages = [
+                capped_relay(&events, &filter, 2),
+                // Always answers with the newest events
+                capped_relay(&events, &Filter::new(), 2),
+            ];
+            listed.extend(window.advance(pages));
+        }
+        assert_eq!(listed.len(), events.len());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,34 @@
+mod tests {
+    use nostr::{EventBuilder, Keys};
+
+    use dex_nostr_relay::pow::{build_event, committed_difficulty, meets_difficulty};
+    use dex_nostr_relay::types::{BLOCKSTREAM_MAKER_CONTENT, CustomKind, MakerOrderKind};
+
+    #[tokio::test]
+    async fn test_mined_event_meets_difficulty() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let builder = EventBuilder::new(MakerOrderKind::get_kind(), BLOCKSTREAM_MAKER_CONTENT);
+
+        let event = build_event(builder, keys.public_key, 8).await?.sign_with_keys(&keys)?;
+
+        assert_eq!(committed_difficulty(&event), Some(8));
+        assert!(meets_difficulty(&event, 8));
+        assert!(!meets_difficulty(&event, 9));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_without_nonce_is_rejected() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let builder = EventBuilder::new(MakerOrderKind::get_kind(), BLOCKSTREAM_MAKER_CONTENT);
+
+        let event = build_event(builder, keys.public_key, 0).await?.sign_with_keys(&keys)?;
+
+        assert_eq!(committed_difficulty(&event), None);
+        assert!(meets_difficulty(&event, 0));
+        assert!(!meets_difficulty(&event, 1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,282 @@
+mod tests {
+    use std::borrow::Cow;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    use futures_util::{SinkExt, StreamExt};
+    use nostr::filter::MatchEventOptions;
+    use nostr::{
+        ClientMessage, Event, EventBuilder, JsonUtil, Keys, Kind, RelayMessage, RelayUrl, Tag, TagKind, Timestamp,
+    };
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::metrics;
+    use dex_nostr_relay::offline::sign_unsigned_event;
+    use dex_nostr_relay::relay_client::ClientConfig;
+    use dex_nostr_relay::relay_processor::{OrderPlaceEventTags, RelayProcessor};
+    use dex_nostr_relay::retry::RetryPolicy;
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind, TakerOrderKind};
+
+    fn accept_all(_: &Event) -> Result<(), &'static str> {
+        Ok(())
+    }
+
+    fn signed_maker_order(maker: &Keys) -> anyhow::Result<Event> {
+        let mut unsigned = EventBuilder::new(MakerOrderKind::get_kind(), "")
+            .tags([
+                Tag::expiration(Timestamp::now() + 3_600),
+                Tag::custom(TagKind::Custom(Cow::from("compiler")), ["simplicity-v1", "abcd"]),
+                Tag::custom(TagKind::Custom(Cow::from("asset_to_buy")), ["22".repeat(32)]),
+                Tag::custom(TagKind::Custom(Cow::from("asset_to_sell")), ["11".repeat(32)]),
+                Tag::custom(TagKind::Custom(Cow::from("price")), ["100"]),
+            ])
+            .build(maker.public_key);
+        unsigned.ensure_id();
+
+        Ok(sign_unsigned_event(unsigned, maker)?)
+    }
+
+    /// Minimal relay answering events by `accept` and subscriptions with end of stored events.
+    async fn spawn_mock_relay(accept: fn(&Event) -> Result<(), &'static str>) -> anyhow::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                        return;
+                    };
+                    while let Some(Ok(message)) = ws.next().await {
+                        let Message::Text(text) = message else {
+                            continue;
+                        };
+                        let reply = match ClientMessage::from_json(text.as_str()) {
+                            Ok(ClientMessage::Event(event)) => match accept(&event) {
+                                Ok(()) => RelayMessage::ok(event.id, true, ""),
+                                Err(message) => RelayMessage::ok(event.id, false, message),
+                            },
+                            Ok(ClientMessage::Req { subscription_id, .. }) => {
+                                RelayMessage::eose(subscription_id.into_owned())
+                            }
+                            _ => continue,
+                        };
+                        if ws.send(Message::text(reply.as_json())).await.is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(url)
+    }
+
+    /// Minimal relay accepting every event and answering subscriptions with the matching `stored` events.
+    async fn spawn_stored_relay(stored: Vec<Event>) -> anyhow::Result<String> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("ws://{}", listener.local_addr()?);
+        let stored = Arc::new(stored);
+
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let stored = Arc::clone(&stored);
+                tokio::spawn(async move {
+                    let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+                        return;
+                    };
+                    while let Some(Ok(message)) = ws.next().await {
+                        let Message::Text(text) = message else {
+                            continue;
+                        };
+                        let replies = match ClientMessage::from_json(text.as_str()) {
+                            Ok(ClientMessage::Event(event)) => vec![RelayMessage::ok(event.id, true, "")],
+                            Ok(ClientMessage::Req {
+                                subscription_id,
+                                filter,
+                            }) => stored
+                                .iter()
+                                .filter(|x| filter.match_event(x, MatchEventOptions::default()))
+                                .map(|x| RelayMessage::event(subscription_id.clone().into_owned(), x.clone()))
+                                .chain([RelayMessage::eose(subscription_id.clone().into_owned())])
+                                .collect(),
+                            _ => continue,
+                        };
+                        for reply in replies {
+                            if ws.send(Message::text(reply.as_json())).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                });
+            }
+        });
+
+        Ok(url)
+    }
+
+    #[tokio::test]
+    async fn test_pre_signed_order_is_published_without_keys() -> anyhow::Result<()> {
+        let event = signed_maker_order(&Keys::generate())?;
+
+        let relay_processor = RelayProcessor::try_from_config(
+            [spawn_mock_relay(accept_all).await?],
+            None::<Keys>,
+            ClientConfig {
+                timeout: Duration::from_secs(5),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let event_id = relay_processor.publish_signed_event(&event).await?;
+        assert_eq!(event_id, event.id);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_reply_is_accepted() -> anyhow::Result<()> {
+        fn reject_duplicates(_: &Event) -> Result<(), &'static str> {
+            Err("duplicate: already have it")
+        }
+
+        let event = signed_maker_order(&Keys::generate())?;
+        let relay_processor = RelayProcessor::try_from_config(
+            [spawn_mock_relay(reject_duplicates).await?],
+            None::<Keys>,
+            ClientConfig {
+                timeout: Duration::from_secs(5),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let event_id = relay_processor.publish_signed_event(&event).await?;
+        assert_eq!(event_id, event.id);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retried_publish_is_not_counted_as_rejected() -> anyhow::Result<()> {
+        static REJECTED_ONCE: AtomicBool = AtomicBool::new(false);
+        fn reject_first(_: &Event) -> Result<(), &'static str> {
+            match REJECTED_ONCE.swap(true, Ordering::SeqCst) {
+                false => Err("error: try again later"),
+                true => Ok(()),
+            }
+        }
+
+        let url = spawn_mock_relay(reject_first).await?;
+        let relay_processor = RelayProcessor::try_from_config(
+            [url.clone()],
+            None::<Keys>,
+            ClientConfig {
+                timeout: Duration::from_secs(5),
+                retry_policy: RetryPolicy {
+                    initial_backoff: Duration::from_millis(10),
+                    jitter: false,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        let event = signed_maker_order(&Keys::generate())?;
+        relay_processor.publish_signed_event(&event).await?;
+
+        let url = RelayUrl::parse(&url)?;
+        let labels = [("relay", url.as_str())];
+        assert_eq!(metrics::PUBLISH_RETRIES.get(&labels), 1);
+        assert_eq!(metrics::EVENTS_PUBLISHED.get(&labels), 1);
+        assert_eq!(metrics::EVENTS_REJECTED.get(&labels), 0);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_withdrawal_is_reported() -> anyhow::Result<()> {
+        fn reject_deletions(event: &Event) -> Result<(), &'static str> {
+            match event.kind {
+                Kind::EventDeletion => Err("blocked: deletions are disabled"),
+                _ => Ok(()),
+            }
+        }
+
+        let relay_processor = RelayProcessor::try_from_config(
+            [spawn_mock_relay(reject_deletions).await?],
+            Some(Keys::generate()),
+            ClientConfig {
+                timeout: Duration::from_secs(5),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let order = OrderPlaceEventTags {
+            asset_to_sell: "11".repeat(32),
+            asset_to_buy: "22".repeat(32),
+            price: 100,
+            compiler_name: "simplicity-v1.2.3".to_string(),
+            compiler_build_hash: "ab12cd".to_string(),
+            ..Default::default()
+        };
+        let invalid_order = OrderPlaceEventTags {
+            compiler_name: String::new(),
+            ..order.clone()
+        };
+
+        let report = relay_processor
+            .place_orders_all_or_nothing(vec![order, invalid_order])
+            .await?;
+        assert_eq!(report.placed.len(), 1);
+        assert_eq!(report.failed.len(), 1);
+        assert!(matches!(report.cancellation, Some(Err(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_accept_reply_picks_the_order_of_the_accepting_maker() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let other_maker = Keys::generate();
+        let taker = Keys::generate();
+        let order = signed_maker_order(&maker)?;
+        let other_order = signed_maker_order(&other_maker)?;
+        let reply = EventBuilder::new(TakerOrderKind::get_kind(), "")
+            .tags([Tag::event(other_order.id), Tag::event(order.id)])
+            .sign_with_keys(&taker)?;
+        let foreign_reply = EventBuilder::new(TakerOrderKind::get_kind(), "")
+            .tags([Tag::event(other_order.id)])
+            .sign_with_keys(&taker)?;
+
+        let relay_processor = RelayProcessor::try_from_config(
+            [spawn_stored_relay(vec![
+                order.clone(),
+                other_order.clone(),
+                reply.clone(),
+                foreign_reply.clone(),
+            ])
+            .await?],
+            Some(maker),
+            ClientConfig {
+                timeout: Duration::from_secs(5),
+                ..Default::default()
+            },
+        )
+        .await?;
+
+        relay_processor.accept_reply(reply.id).await?;
+        let accepted = relay_processor.accept_reply(foreign_reply.id).await;
+        assert!(matches!(
+            accepted,
+            Err(NostrRelayError::NotOrderAuthor { order_id }) if order_id == other_order.id
+        ));
+
+        Ok(())
+    }
+}
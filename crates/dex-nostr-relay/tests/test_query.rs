@@ -0,0 +1,59 @@
+mod tests {
+    use std::collections::BTreeSet;
+
+    use nostr::{EventBuilder, Filter, Keys, Kind, Tag, Timestamp};
+
+    use dex_nostr_relay::query::QueryOptions;
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind};
+
+    #[test]
+    fn test_options_narrow_relay_filter() {
+        let author = Keys::generate().public_key;
+        let options = QueryOptions {
+            since: Some(Timestamp::from(100)),
+            until: Some(Timestamp::from(200)),
+            authors: Some(BTreeSet::from([author])),
+            limit: Some(5),
+            include_expired: false,
+        };
+
+        let filter = options.apply(
+            Filter::new()
+                .kind(MakerOrderKind::get_kind())
+                .until(Timestamp::from(300)),
+        );
+        assert_eq!(filter.since, Some(Timestamp::from(100)));
+        assert_eq!(filter.until, Some(Timestamp::from(200)));
+        assert_eq!(filter.authors, Some(BTreeSet::from([author])));
+        assert_eq!(filter.kinds, Some(BTreeSet::from([MakerOrderKind::get_kind()])));
+        // Limit is applied client-side after expired events are dropped
+        assert_eq!(filter.limit, None);
+
+        let filter = QueryOptions::default().apply(Filter::new().since(Timestamp::from(50)));
+        assert_eq!(filter.since, Some(Timestamp::from(50)));
+        assert_eq!(filter.until, None);
+    }
+
+    #[test]
+    fn test_expired_events_visibility() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let expired = EventBuilder::new(Kind::TextNote, "")
+            .tag(Tag::expiration(Timestamp::from(100)))
+            .sign_with_keys(&keys)?;
+        let permanent = EventBuilder::new(Kind::TextNote, "").sign_with_keys(&keys)?;
+        let now = Timestamp::from(200);
+
+        let live_only = QueryOptions::default();
+        assert!(!live_only.is_visible(&expired, now));
+        assert!(live_only.is_visible(&permanent, now));
+        assert!(live_only.is_visible(&expired, Timestamp::from(50)));
+
+        let historical = QueryOptions {
+            include_expired: true,
+            ..Default::default()
+        };
+        assert!(historical.is_visible(&expired, now));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+mod tests {
+    use std::time::Duration;
+
+    use nostr::RelayUrl;
+    use nostr_sdk::RelayStatus;
+
+    use dex_nostr_relay::relay_health::{RelayHealthTracker, RelayOperation};
+
+    #[test]
+    fn test_relay_demoted_after_consecutive_failures() -> anyhow::Result<()> {
+        let url = RelayUrl::parse("wss://relay.example.com")?;
+        let tracker = RelayHealthTracker::new(2, Duration::from_secs(60));
+
+        tracker.record_success(&url, RelayOperation::Fetch, Some(Duration::from_millis(120)));
+        tracker.record_failure(&url, RelayOperation::Publish, "rejected");
+        assert!(!tracker.is_demoted(&url));
+
+        tracker.record_failure(&url, RelayOperation::Publish, "timeout");
+        assert!(tracker.is_demoted(&url));
+
+        let health = tracker.health(&url, RelayStatus::Connected, None);
+        assert!(!health.is_healthy());
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(health.last_error.as_deref(), Some("timeout"));
+        assert_eq!(health.latency, Some(Duration::from_millis(120)));
+        assert_eq!(health.fetch_success_rate, Some(1.0));
+        assert_eq!(health.publish_success_rate, Some(0.0));
+
+        tracker.record_success(&url, RelayOperation::Publish, None);
+        assert!(!tracker.is_demoted(&url));
+        assert_eq!(
+            tracker.health(&url, RelayStatus::Connected, None).publish_success_rate,
+            Some(1.0 / 3.0)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relay_demotion_expires_after_cooldown() -> anyhow::Result<()> {
+        let url = RelayUrl::parse("wss://relay.example.com")?;
+        let tracker = RelayHealthTracker::new(1, Duration::from_millis(200));
+
+        tracker.record_failure(&url, RelayOperation::Fetch, "connection refused");
+        assert!(tracker.is_demoted(&url));
+        assert!(!tracker.health(&url, RelayStatus::Connected, None).is_healthy());
+
+        std::thread::sleep(Duration::from_millis(250));
+        assert!(!tracker.is_demoted(&url));
+        assert!(tracker.health(&url, RelayStatus::Connected, None).is_healthy());
+
+        let health = tracker.health(&url, RelayStatus::Terminated, None);
+        assert!(!health.demoted);
+        assert!(!health.is_healthy());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relay_without_cooldown_is_not_demoted() -> anyhow::Result<()> {
+        let url = RelayUrl::parse("wss://relay.example.com")?;
+        let tracker = RelayHealthTracker::new(1, Duration::ZERO);
+
+        tracker.record_failure(&url, RelayOperation::Fetch, "connection refused");
+        assert!(!tracker.is_demoted(&url));
+        assert!(tracker.health(&url, RelayStatus::Connected, None).is_healthy());
+
+        Ok(())
+    }
+}
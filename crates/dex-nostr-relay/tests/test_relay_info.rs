@@ -0,0 +1,39 @@
+mod tests {
+    use nostr::RelayUrl;
+    use nostr::nips::nip11::RelayInformationDocument;
+
+    use dex_nostr_relay::relay_info::{NIP_EXPIRATION, UnsupportedExpirationPolicy, supports_nip};
+
+    fn document(supported_nips: Option<Vec<u16>>) -> RelayInformationDocument {
+        RelayInformationDocument {
+            supported_nips,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_supports_nip() {
+        assert!(supports_nip(&document(Some(vec![1, 9, 40])), NIP_EXPIRATION));
+        assert!(!supports_nip(&document(Some(vec![1, 9])), NIP_EXPIRATION));
+        assert!(!supports_nip(&document(None), NIP_EXPIRATION));
+    }
+
+    #[test]
+    fn test_unsupported_expiration_policy() -> anyhow::Result<()> {
+        let url = RelayUrl::parse("wss://relay.example.com")?;
+        let supported = document(Some(vec![NIP_EXPIRATION]));
+        let unsupported = document(Some(vec![1]));
+
+        for policy in [UnsupportedExpirationPolicy::Warn, UnsupportedExpirationPolicy::Skip] {
+            assert!(policy.accepts_relay(&url, Some(&supported)));
+        }
+
+        assert!(UnsupportedExpirationPolicy::Warn.accepts_relay(&url, Some(&unsupported)));
+        assert!(UnsupportedExpirationPolicy::Warn.accepts_relay(&url, None));
+
+        assert!(!UnsupportedExpirationPolicy::Skip.accepts_relay(&url, Some(&unsupported)));
+        assert!(!UnsupportedExpirationPolicy::Skip.accepts_relay(&url, None));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::HashSet;
+    use std::time::Duration;
+
+    use nostr::nips::nip09::EventDeletionRequest;
+    use nostr::{Event, EventBuilder, EventId, Keys, Tag, TagKind, Timestamp};
+
+    use dex_nostr_relay::reputation::{UNTRUSTED_MAKER_WEIGHT, compute_reputation};
+    use dex_nostr_relay::types::{CustomKind, MakerAcceptKind, MakerOrderKind, TakerOrderKind};
+
+    fn sign(builder: EventBuilder, keys: &Keys, created_at: u64) -> anyhow::Result<Event> {
+        Ok(builder
+            .custom_created_at(Timestamp::from(created_at))
+            .sign_with_keys(keys)?)
+    }
+
+    fn reply(order_id: EventId, taker: &Keys, created_at: u64) -> anyhow::Result<Event> {
+        sign(
+            EventBuilder::new(TakerOrderKind::get_kind(), "").tags([Tag::event(order_id)]),
+            taker,
+            created_at,
+        )
+    }
+
+    #[test]
+    fn test_maker_reputation_metrics() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let taker = Keys::generate();
+
+        let settled_order = sign(EventBuilder::new(MakerOrderKind::get_kind(), ""), &maker, 100)?;
+        let cancelled_order = sign(EventBuilder::new(MakerOrderKind::get_kind(), ""), &maker, 110)?;
+        let idle_order = sign(EventBuilder::new(MakerOrderKind::get_kind(), ""), &maker, 120)?;
+
+        let settled_reply = reply(settled_order.id, &taker, 200)?;
+        let cancelled_reply = reply(cancelled_order.id, &taker, 210)?;
+
+        let accept = sign(
+            EventBuilder::new(MakerAcceptKind::get_kind(), "").tags([
+                Tag::public_key(taker.public_key),
+                Tag::event(settled_order.id),
+                Tag::custom(TagKind::Custom(Cow::from("reply_event_id")), [settled_reply.id]),
+            ]),
+            &maker,
+            260,
+        )?;
+        let deletion = sign(
+            EventBuilder::delete(EventDeletionRequest::new().ids([cancelled_order.id, idle_order.id])),
+            &maker,
+            300,
+        )?;
+
+        let events = vec![
+            settled_order,
+            cancelled_order,
+            idle_order,
+            settled_reply,
+            cancelled_reply,
+            accept,
+            deletion,
+        ];
+
+        let reputation = compute_reputation(maker.public_key, &events, None);
+        assert_eq!(reputation.orders_placed, 3);
+        assert_eq!(reputation.replies_received, 2);
+        assert_eq!(reputation.trades_settled, 1);
+        assert_eq!(reputation.cancellations_after_funding, 1);
+        assert_eq!(reputation.average_time_to_accept, Some(Duration::from_secs(60)));
+        assert_eq!(reputation.score, Some(0.25));
+
+        let trusted = HashSet::from([taker.public_key]);
+        let reputation = compute_reputation(maker.public_key, &events, Some(&trusted));
+        assert_eq!(reputation.trust_weight, UNTRUSTED_MAKER_WEIGHT);
+        assert_eq!(reputation.score, Some(0.25 * UNTRUSTED_MAKER_WEIGHT));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_uncorroborated_accepts_are_not_counted() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let taker = Keys::generate();
+
+        let order = sign(EventBuilder::new(MakerOrderKind::get_kind(), ""), &maker, 100)?;
+        let other_order = sign(EventBuilder::new(MakerOrderKind::get_kind(), ""), &maker, 110)?;
+        let self_reply = reply(order.id, &maker, 200)?;
+        let taker_reply = reply(order.id, &taker, 210)?;
+
+        let accept = |reply: &Event, order_id: EventId, taker: &Keys| {
+            sign(
+                EventBuilder::new(MakerAcceptKind::get_kind(), "").tags([
+                    Tag::public_key(taker.public_key),
+                    Tag::event(order_id),
+                    Tag::custom(TagKind::Custom(Cow::from("reply_event_id")), [reply.id]),
+                ]),
+                &maker,
+                300,
+            )
+        };
+        let events = vec![
+            accept(&self_reply, order.id, &maker)?,
+            accept(&taker_reply, other_order.id, &taker)?,
+            accept(&taker_reply, order.id, &Keys::generate())?,
+            order,
+            other_order,
+            self_reply,
+            taker_reply,
+        ];
+
+        let reputation = compute_reputation(maker.public_key, &events, None);
+        assert_eq!(reputation.replies_received, 1);
+        assert_eq!(reputation.trades_settled, 0);
+        assert_eq!(reputation.score, Some(0.0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_maker_without_replies_has_no_score() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let order = sign(EventBuilder::new(MakerOrderKind::get_kind(), ""), &maker, 100)?;
+
+        let reputation = compute_reputation(maker.public_key, &[order], None);
+        assert_eq!(reputation.orders_placed, 1);
+        assert_eq!(reputation.score, None);
+
+        Ok(())
+    }
+}
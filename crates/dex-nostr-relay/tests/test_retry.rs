@@ -0,0 +1,49 @@
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use dex_nostr_relay::retry::RetryPolicy;
+
+    #[test]
+    fn test_backoff_grows_exponentially_up_to_cap() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff(10), policy.max_backoff);
+        assert_eq!(policy.backoff(usize::MAX), policy.max_backoff);
+
+        let policy = RetryPolicy::default();
+        for attempt in 1..8 {
+            let capped = policy
+                .initial_backoff
+                .saturating_mul(1 << (attempt - 1))
+                .min(policy.max_backoff);
+            let backoff = policy.backoff(attempt);
+            assert!(
+                backoff >= capped / 2 && backoff <= capped,
+                "{backoff:?} is out of jitter range"
+            );
+        }
+    }
+
+    #[test]
+    fn test_attempts_and_deadline_limit_retries() {
+        let policy = RetryPolicy::default();
+        let started_at = Instant::now();
+
+        assert!(policy.next_delay(1, started_at).is_some());
+        assert!(policy.next_delay(2, started_at).is_some());
+        assert!(policy.next_delay(3, started_at).is_none());
+        assert!(RetryPolicy::no_retries().next_delay(1, started_at).is_none());
+
+        let short_deadline = RetryPolicy {
+            deadline: Duration::from_millis(100),
+            ..Default::default()
+        };
+        assert!(short_deadline.next_delay(1, started_at).is_none());
+    }
+}
@@ -0,0 +1,112 @@
+mod tests {
+    use std::borrow::Cow;
+    use std::time::Duration;
+
+    use nostr::{EventBuilder, Keys, Tag, TagKind, Timestamp};
+
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::rfq::{ENCRYPTED_QUOTE_SCHEME, Quote, QuoteRequest, QuoteRequestTags, QuoteTerms};
+    use dex_nostr_relay::types::{CustomKind, QuoteKind, QuoteRequestKind};
+
+    #[test]
+    fn test_quote_request_parsing() -> anyhow::Result<()> {
+        let taker = Keys::generate();
+        let tags = QuoteRequestTags {
+            asset_to_sell: "11".repeat(32),
+            asset_to_buy: "22".repeat(32),
+            amount: 1_000,
+            expires_in: Duration::from_secs(300),
+        };
+        let expiration = Timestamp::from(2_000);
+        let event = EventBuilder::new(QuoteRequestKind::get_kind(), "")
+            .tags([Tag::expiration(expiration)])
+            .tags(tags.to_tags())
+            .sign_with_keys(&taker)?;
+
+        let request = QuoteRequest::try_from(&event)?;
+        assert_eq!(request.requester, taker.public_key);
+        assert_eq!(request.asset_to_buy, tags.asset_to_buy);
+        assert_eq!(request.amount, 1_000);
+        assert_eq!(request.expires_at, Some(expiration));
+
+        let quote_event = EventBuilder::new(QuoteKind::get_kind(), "").sign_with_keys(&taker)?;
+        assert!(matches!(
+            QuoteRequest::try_from(&quote_event),
+            Err(NostrRelayError::UnexpectedEventKind { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_parsing_plain_and_encrypted() -> anyhow::Result<()> {
+        let maker = Keys::generate();
+        let request = EventBuilder::new(QuoteRequestKind::get_kind(), "").sign_with_keys(&Keys::generate())?;
+        let terms = QuoteTerms { price: 42, amount: 500 };
+
+        let plain = EventBuilder::new(QuoteKind::get_kind(), "")
+            .tags([Tag::event(request.id)])
+            .tags(terms.to_tags())
+            .sign_with_keys(&maker)?;
+        let quote = Quote::from_event(&plain, None)?;
+        assert_eq!(quote.request_id, request.id);
+        assert_eq!(quote.terms, terms);
+        assert!(!quote.encrypted);
+
+        let encrypted = EventBuilder::new(QuoteKind::get_kind(), "ciphertext")
+            .tags([
+                Tag::event(request.id),
+                Tag::custom(TagKind::Custom(Cow::from("encrypted")), [ENCRYPTED_QUOTE_SCHEME]),
+            ])
+            .sign_with_keys(&maker)?;
+        assert!(Quote::is_encrypted_event(&encrypted));
+        assert!(Quote::from_event(&encrypted, None).is_err());
+
+        let quote = Quote::from_event(&encrypted, Some(&serde_json::to_string(&terms)?))?;
+        assert_eq!(quote.terms, terms);
+        assert!(quote.encrypted);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_quote_validation() -> anyhow::Result<()> {
+        let tags = QuoteRequestTags {
+            asset_to_sell: "11".repeat(32),
+            asset_to_buy: "22".repeat(32),
+            amount: 1_000,
+            expires_in: Duration::from_secs(300),
+        };
+        tags.validate()?;
+        assert!(matches!(
+            QuoteRequestTags {
+                asset_to_buy: "L-BTC".to_string(),
+                ..tags.clone()
+            }
+            .validate(),
+            Err(NostrRelayError::InvalidOrderTag { tag, .. }) if tag == "asset_to_buy"
+        ));
+
+        let event = EventBuilder::new(QuoteRequestKind::get_kind(), "")
+            .tags(tags.to_tags())
+            .sign_with_keys(&Keys::generate())?;
+        let request = QuoteRequest::try_from(&event)?;
+
+        QuoteTerms {
+            price: 42,
+            amount: 1_000,
+        }
+        .validate_for(&request)?;
+        assert!(
+            QuoteTerms {
+                price: 42,
+                amount: 1_001
+            }
+            .validate_for(&request)
+            .is_err()
+        );
+        assert!(QuoteTerms { price: 0, amount: 500 }.validate_for(&request).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+mod tests {
+    use std::time::Duration;
+
+    use std::str::FromStr;
+
+    use elements::{AssetId, OutPoint, Script, TxOut, TxOutWitness, confidential};
+    use nostr::{EventBuilder, Keys, Timestamp};
+
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::option_terms::{OptionStyle, OptionTerms, OptionType, Settlement};
+    use dex_nostr_relay::relay_processor::{OrderPlaceEventTags, OrderReplyEventTags};
+    use dex_nostr_relay::settlement::{
+        OrderThread, SettlementParams, SettlementRole, build_settlement_pset, compute_payout, settlement_timestamp,
+    };
+    use dex_nostr_relay::swap::SwapInput;
+    use dex_nostr_relay::types::{CustomKind, MakerOrderKind, TakerOrderKind};
+
+    const CONTRACT_CMR: &str = "fc5770fe4305f810434a42103c363f872190515a916e590e125241104340978f";
+
+    fn fee_input(asset: AssetId, amount: u64, script: &Script) -> anyhow::Result<SwapInput> {
+        Ok(SwapInput {
+            outpoint: OutPoint::from_str(&format!("{}:0", "cd".repeat(32)))?,
+            utxo: TxOut {
+                asset: confidential::Asset::Explicit(asset),
+                value: confidential::Value::Explicit(amount),
+                nonce: confidential::Nonce::Null,
+                script_pubkey: script.clone(),
+                witness: TxOutWitness::default(),
+            },
+        })
+    }
+
+    fn terms(option_type: OptionType, style: OptionStyle) -> OptionTerms {
+        OptionTerms {
+            option_type,
+            style,
+            strike_price: 100,
+            settlement: Settlement::Time(1_000),
+            collateral_asset: "11".repeat(32),
+            settlement_asset: "22".repeat(32),
+        }
+    }
+
+    #[test]
+    fn test_option_payout() -> anyhow::Result<()> {
+        let call = terms(OptionType::Call, OptionStyle::European);
+        let payout = compute_payout(&call, 1_000, 125)?;
+        assert_eq!(payout.taker_amount, 200);
+        assert_eq!(payout.maker_amount, 800);
+        assert_eq!(compute_payout(&call, 1_000, 80)?.taker_amount, 0);
+
+        let put = terms(OptionType::Put, OptionStyle::European);
+        assert_eq!(compute_payout(&put, 1_000, 80)?.taker_amount, 250);
+        assert_eq!(compute_payout(&put, 1_000, 10)?.taker_amount, 1_000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_settlement_time_depends_on_style() -> anyhow::Result<()> {
+        let european = terms(OptionType::Call, OptionStyle::European);
+        let american = terms(OptionType::Call, OptionStyle::American);
+        let before = Timestamp::from(500);
+        let after = Timestamp::from(2_000);
+
+        assert!(matches!(
+            settlement_timestamp(&european, SettlementRole::Taker, before),
+            Err(NostrRelayError::InvalidSettlement { .. })
+        ));
+        assert_eq!(settlement_timestamp(&american, SettlementRole::Taker, before)?, before);
+        assert!(settlement_timestamp(&american, SettlementRole::Maker, before).is_err());
+        assert_eq!(
+            settlement_timestamp(&european, SettlementRole::Maker, after)?,
+            Timestamp::from(1_000)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_settlement_pset_skeleton() -> anyhow::Result<()> {
+        let keys = Keys::generate();
+        let order_tags = OrderPlaceEventTags {
+            contract_cmr: Some(CONTRACT_CMR.to_string()),
+            option_terms: Some(terms(OptionType::Call, OptionStyle::European)),
+            ..Default::default()
+        };
+        let reply_tags = OrderReplyEventTags {
+            tx_id: "ab".repeat(32),
+            swap_pset: None,
+        };
+        let thread = OrderThread {
+            order: EventBuilder::new(MakerOrderKind::get_kind(), "").sign_with_keys(&keys)?,
+            order_tags,
+            reply: EventBuilder::new(TakerOrderKind::get_kind(), "").sign_with_keys(&keys)?,
+            reply_tags,
+        };
+        let taker_script = Script::from(vec![0x52]);
+        let params = SettlementParams {
+            contract_vout: 1,
+            collateral_amount: 1_000,
+            maker_script: Script::from(vec![0x51]),
+            taker_script: taker_script.clone(),
+            fee: 50,
+            fee_asset: AssetId::LIQUID_BTC,
+            fee_input: Some(fee_input(AssetId::LIQUID_BTC, 1_000, &taker_script)?),
+            oracle_tolerance: Duration::from_secs(600),
+        };
+
+        // Collateral is not L-BTC, fee is paid from the separate L-BTC input of the taker
+        let collateral_asset = AssetId::from_str(&"11".repeat(32))?;
+        let payout = compute_payout(thread.order_tags.option_terms.as_ref().unwrap(), 1_000, 125)?;
+        let pset = build_settlement_pset(&thread, SettlementRole::Taker, &params, payout)?;
+        let tx = pset.extract_tx()?;
+        assert_eq!(tx.input.len(), 2);
+        assert_eq!(tx.input[0].previous_output.vout, 1);
+        let outputs = tx
+            .output
+            .iter()
+            .map(|x| (x.asset.explicit(), x.value.explicit()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outputs,
+            vec![
+                (Some(collateral_asset), Some(200)),
+                (Some(collateral_asset), Some(800)),
+                (Some(AssetId::LIQUID_BTC), Some(50)),
+                (Some(AssetId::LIQUID_BTC), Some(950)),
+            ]
+        );
+        assert!(tx.output[2].script_pubkey.is_empty());
+        assert_eq!(tx.output[3].script_pubkey, taker_script);
+
+        let free = SettlementParams {
+            fee: 0,
+            fee_input: None,
+            ..params.clone()
+        };
+        let tx = build_settlement_pset(&thread, SettlementRole::Taker, &free, payout)?.extract_tx()?;
+        assert_eq!(tx.input.len(), 1);
+        let amounts = tx.output.iter().map(|x| x.value.explicit()).collect::<Vec<_>>();
+        assert_eq!(amounts, vec![Some(200), Some(800)]);
+
+        for invalid in [
+            SettlementParams {
+                fee_input: None,
+                ..params.clone()
+            },
+            SettlementParams {
+                fee_input: Some(fee_input(collateral_asset, 1_000, &taker_script)?),
+                ..params.clone()
+            },
+            SettlementParams { fee: 1_001, ..params },
+        ] {
+            assert!(matches!(
+                build_settlement_pset(&thread, SettlementRole::Taker, &invalid, payout),
+                Err(NostrRelayError::InvalidSettlement { .. })
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+mod tests {
+    use std::str::FromStr;
+
+    use elements::bitcoin::PublicKey;
+    use elements::confidential::{Asset, Nonce, Value};
+    use elements::hashes::Hash;
+    use elements::{AssetId, OutPoint, Script, TxOut, TxOutWitness, Txid, WPubkeyHash};
+
+    use nostr::{EventId, Keys};
+
+    use dex_nostr_relay::error::NostrRelayError;
+    use dex_nostr_relay::relay_client::ClientConfig;
+    use dex_nostr_relay::relay_processor::{OrderPlaceEventTags, OrderReplyEventTags, RelayProcessor};
+    use dex_nostr_relay::swap::{SwapInput, SwapProposalParams};
+
+    const MAKER_PUBKEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const TAKER_PUBKEY: &str = "02c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5";
+
+    fn p2wpkh(pubkey: &PublicKey) -> Script {
+        Script::new_v0_wpkh(&WPubkeyHash::hash(&pubkey.to_bytes()))
+    }
+
+    fn input(vout: u32, script: &Script, asset: AssetId, value: u64) -> SwapInput {
+        SwapInput {
+            outpoint: OutPoint::new(Txid::all_zeros(), vout),
+            utxo: TxOut {
+                asset: Asset::Explicit(asset),
+                value: Value::Explicit(value),
+                nonce: Nonce::Null,
+                script_pubkey: script.clone(),
+                witness: TxOutWitness::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_swap_proposal_lifecycle() -> anyhow::Result<()> {
+        let asset_to_sell = AssetId::from_slice(&[1; 32])?;
+        let asset_to_buy = AssetId::from_slice(&[2; 32])?;
+        let fee_asset = AssetId::from_slice(&[3; 32])?;
+        let maker_pubkey = PublicKey::from_str(MAKER_PUBKEY)?;
+        let taker_pubkey = PublicKey::from_str(TAKER_PUBKEY)?;
+        let maker_script = p2wpkh(&maker_pubkey);
+        let taker_script = p2wpkh(&taker_pubkey);
+
+        let order = OrderPlaceEventTags {
+            asset_to_sell: asset_to_sell.to_string(),
+            asset_to_buy: asset_to_buy.to_string(),
+            price: 400,
+            ..Default::default()
+        };
+        let params = SwapProposalParams {
+            maker_inputs: vec![input(0, &maker_script, asset_to_sell, 10)],
+            taker_inputs: vec![
+                input(1, &taker_script, asset_to_buy, 5_000),
+                input(2, &taker_script, fee_asset, 1_000),
+            ],
+            maker_script: maker_script.clone(),
+            taker_script,
+            sell_amount: 10,
+            fee: 100,
+            fee_asset,
+        };
+
+        let pset = RelayProcessor::build_swap_proposal(&order, &params)?;
+        let summary = RelayProcessor::validate_swap_proposal(&pset, &order, &maker_script)?;
+        assert_eq!(summary.sold_amount, 10);
+        assert_eq!(summary.received_amount, 4_000);
+        assert_eq!(summary.fee, 100);
+
+        let smaller_order = OrderPlaceEventTags {
+            amount: Some(9),
+            ..order.clone()
+        };
+        assert!(matches!(
+            RelayProcessor::validate_swap_proposal(&pset, &smaller_order, &maker_script),
+            Err(NostrRelayError::InvalidSwapProposal { .. })
+        ));
+        assert!(matches!(
+            RelayProcessor::build_swap_proposal(&smaller_order, &params),
+            Err(NostrRelayError::InvalidSwapProposal { .. })
+        ));
+        let exact_order = OrderPlaceEventTags {
+            amount: Some(10),
+            ..order.clone()
+        };
+        RelayProcessor::validate_swap_proposal(&pset, &exact_order, &maker_script)?;
+
+        let mut underpaying = pset.clone();
+        underpaying.outputs_mut()[1].amount = Some(3_999);
+        assert!(matches!(
+            RelayProcessor::validate_swap_proposal(&underpaying, &order, &maker_script),
+            Err(NostrRelayError::InvalidSwapProposal { .. })
+        ));
+
+        let mut taker_signed = pset.clone();
+        for input in &mut taker_signed.inputs_mut()[1..] {
+            input.partial_sigs.insert(taker_pubkey, vec![0x30; 71]);
+        }
+        assert!(matches!(
+            RelayProcessor::finalize_swap_proposal(pset.clone(), taker_signed.clone()),
+            Err(NostrRelayError::InvalidSwapProposal { .. })
+        ));
+
+        let mut maker_signed = pset;
+        maker_signed.inputs_mut()[0]
+            .partial_sigs
+            .insert(maker_pubkey, vec![0x30; 71]);
+        let tx = RelayProcessor::finalize_swap_proposal(taker_signed, maker_signed)?;
+        assert_eq!(tx.input.len(), 3);
+        assert_eq!(tx.output.len(), 5);
+        assert!(tx.input.iter().all(|x| x.witness.script_witness.len() == 2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_swap_proposal_requires_enough_funds() -> anyhow::Result<()> {
+        let asset_to_sell = AssetId::from_slice(&[1; 32])?;
+        let asset_to_buy = AssetId::from_slice(&[2; 32])?;
+        let script = p2wpkh(&PublicKey::from_str(MAKER_PUBKEY)?);
+
+        let order = OrderPlaceEventTags {
+            asset_to_sell: asset_to_sell.to_string(),
+            asset_to_buy: asset_to_buy.to_string(),
+            price: 400,
+            ..Default::default()
+        };
+        let params = SwapProposalParams {
+            maker_inputs: vec![input(0, &script, asset_to_sell, 10)],
+            taker_inputs: vec![input(1, &script, asset_to_buy, 3_999)],
+            maker_script: script.clone(),
+            taker_script: script,
+            sell_amount: 10,
+            fee: 0,
+            fee_asset: asset_to_buy,
+        };
+
+        assert!(matches!(
+            RelayProcessor::build_swap_proposal(&order, &params),
+            Err(NostrRelayError::InvalidSwapProposal { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_swap_reply_omits_empty_tx_id() -> anyhow::Result<()> {
+        let asset_to_sell = AssetId::from_slice(&[1; 32])?;
+        let asset_to_buy = AssetId::from_slice(&[2; 32])?;
+        let maker_script = p2wpkh(&PublicKey::from_str(MAKER_PUBKEY)?);
+        let taker_script = p2wpkh(&PublicKey::from_str(TAKER_PUBKEY)?);
+
+        let order = OrderPlaceEventTags {
+            asset_to_sell: asset_to_sell.to_string(),
+            asset_to_buy: asset_to_buy.to_string(),
+            price: 400,
+            ..Default::default()
+        };
+        let pset = RelayProcessor::build_swap_proposal(
+            &order,
+            &SwapProposalParams {
+                maker_inputs: vec![input(0, &maker_script, asset_to_sell, 10)],
+                taker_inputs: vec![input(1, &taker_script, asset_to_buy, 5_000)],
+                maker_script,
+                taker_script,
+                sell_amount: 10,
+                fee: 100,
+                fee_asset: asset_to_buy,
+            },
+        )?;
+
+        let maker = Keys::generate();
+        let taker = Keys::generate();
+        let relay_processor =
+            RelayProcessor::try_from_config(Vec::<String>::new(), None::<Keys>, ClientConfig::default()).await?;
+        let reply = relay_processor
+            .build_reply(
+                EventId::all_zeros(),
+                maker.public_key,
+                OrderReplyEventTags {
+                    tx_id: String::new(),
+                    swap_pset: Some(pset.to_string()),
+                },
+                taker.public_key,
+            )
+            .await?
+            .sign_with_keys(&taker)?;
+
+        assert!(reply.tags.iter().all(|tag| tag.kind().to_string() != "tx_id"));
+        let tags = OrderReplyEventTags::try_from(&reply)?;
+        assert!(tags.tx_id.is_empty());
+        assert_eq!(tags.swap_pset, Some(pset.to_string()));
+
+        Ok(())
+    }
+}
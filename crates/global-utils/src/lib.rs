@@ -1 +1,3 @@
 pub mod logger;
+pub mod metrics;
+pub mod secret;
@@ -1,47 +1,336 @@
-use std::io;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
 
-use tracing::{level_filters::LevelFilter, trace};
+use regex::Regex;
+
+use serde::Deserialize;
+use tracing::{Metadata, Subscriber, level_filters::LevelFilter, trace};
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{EnvFilter, Layer, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::format::{Format, Json, JsonFields};
+use tracing_subscriber::layer::Filter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, fmt as tracing_fmt, layer::SubscriberExt, util::SubscriberInitExt,
+};
 
 const ENV_VAR_NAME: &str = "DEX_LOG";
 const DEFAULT_LOG_DIRECTIVE: LevelFilter = LevelFilter::ERROR;
+const DEFAULT_LOG_FILE_PREFIX: &str = "simplicity-dex";
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+static BECH32_SECRET: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b(nsec|ncryptsec)1[02-9ac-hj-np-z]{20,}").expect("valid regex"));
+static HEX_SECRET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)((?:secret|private|priv_?key|seed|password)[\w\s"'=:(\[\x1b;]{0,24}?)\b[0-9a-f]{64}\b"#)
+        .expect("valid regex")
+});
+
+#[derive(thiserror::Error, Debug)]
+pub enum LoggerError {
+    #[error("Failed to read logger config from {source_name}, err: {source}")]
+    FailedToReadConfig {
+        source_name: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Failed to parse logger config from {source_name}, err: {source}")]
+    FailedToParseConfig {
+        source_name: String,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("Failed to open log file in {directory}, err: {source}")]
+    FailedToOpenLogFile {
+        directory: PathBuf,
+        #[source]
+        source: tracing_appender::rolling::InitError,
+    },
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line, for log collectors
+    Json,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    #[default]
+    Daily,
+    Hourly,
+    Never,
+}
+
+/// Rotating log file written next to console output.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct LogFileConfig {
+    pub directory: PathBuf,
+    #[serde(default = "default_log_file_prefix")]
+    pub file_name_prefix: String,
+    #[serde(default)]
+    pub rotation: LogRotation,
+    /// Rotated files to keep, older ones are removed, all files are kept if `None`
+    pub max_files: Option<usize>,
+}
+
+/// Logger setup for [`init_logger_with_config`].
+///
+/// Loaded from TOML:
+/// ```toml
+/// level = "info"
+/// format = "json"
+///
+/// [targets]
+/// dex_nostr_relay = "debug"
+/// nostr_sdk = "warn"
+///
+/// [file]
+/// directory = "/var/log/simplicity-dex"
+/// rotation = "hourly"
+/// max_files = 48
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct LoggerConfig {
+    /// Default level, directives from the `DEX_LOG` environment variable take precedence over the config
+    pub level: String,
+    /// Levels per target, e.g. `dex_nostr_relay = "debug"`
+    pub targets: BTreeMap<String, String>,
+    pub format: LogFormat,
+    /// Write all console logs to stderr, so stdout carries only command output
+    pub machine_output: bool,
+    pub file: Option<LogFileConfig>,
+}
 
 #[derive(Debug)]
 pub struct LoggerGuard {
-    _std_out_guard: WorkerGuard,
-    _std_err_guard: WorkerGuard,
+    _guards: Vec<WorkerGuard>,
 }
 
-pub fn init_logger() -> LoggerGuard {
-    let (std_out_writer, std_out_guard) = tracing_appender::non_blocking(io::stdout());
-    let (std_err_writer, std_err_guard) = tracing_appender::non_blocking(io::stderr());
-    let std_out_layer = fmt::layer()
-        .with_writer(std_out_writer)
-        .with_target(false)
-        .with_level(true)
-        .with_filter(
-            EnvFilter::builder()
+/// Redaction layer of the logger, wraps writers of formatting layers and masks secrets, see [`redact`].
+#[derive(Debug, Clone)]
+pub struct RedactingMakeWriter<M>(M);
+
+#[derive(Debug)]
+pub struct RedactingWriter<W>(W);
+
+fn default_log_file_prefix() -> String {
+    DEFAULT_LOG_FILE_PREFIX.to_string()
+}
+
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        Self {
+            level: DEFAULT_LOG_DIRECTIVE.to_string(),
+            targets: BTreeMap::new(),
+            format: LogFormat::default(),
+            machine_output: false,
+            file: None,
+        }
+    }
+}
+
+impl LoggerConfig {
+    pub fn from_toml_str(config: &str) -> Result<Self, LoggerError> {
+        toml::from_str(config).map_err(|source| LoggerError::FailedToParseConfig {
+            source_name: "toml".to_string(),
+            source,
+        })
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, LoggerError> {
+        let path = path.as_ref();
+
+        let config = std::fs::read_to_string(path).map_err(|source| LoggerError::FailedToReadConfig {
+            source_name: path.display().to_string(),
+            source,
+        })?;
+        toml::from_str(&config).map_err(|source| LoggerError::FailedToParseConfig {
+            source_name: path.display().to_string(),
+            source,
+        })
+    }
+
+    /// Directives of the config, e.g. `info,dex_nostr_relay=debug`.
+    pub fn directives(&self) -> String {
+        std::iter::once(self.level.clone())
+            .chain(self.targets.iter().map(|(target, level)| format!("{target}={level}")))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+
+    fn env_filter(&self) -> EnvFilter {
+        match std::env::var(ENV_VAR_NAME) {
+            Ok(directives) if !directives.trim().is_empty() => EnvFilter::builder()
                 .with_default_directive(DEFAULT_LOG_DIRECTIVE.into())
-                .with_env_var(ENV_VAR_NAME)
-                .from_env_lossy(),
-        );
+                .parse_lossy(directives),
+            _ => EnvFilter::builder()
+                .with_default_directive(DEFAULT_LOG_DIRECTIVE.into())
+                .parse_lossy(self.directives()),
+        }
+    }
+}
 
-    let std_err_layer = fmt::layer()
-        .with_writer(std_err_writer)
-        .with_target(false)
-        .with_level(true)
-        .with_filter(LevelFilter::WARN);
+impl From<LogRotation> for Rotation {
+    fn from(rotation: LogRotation) -> Self {
+        match rotation {
+            LogRotation::Daily => Rotation::DAILY,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Never => Rotation::NEVER,
+        }
+    }
+}
 
-    tracing_subscriber::registry()
-        .with(std_out_layer)
-        .with(std_err_layer)
-        .init();
+impl LogFileConfig {
+    fn appender(&self) -> Result<RollingFileAppender, LoggerError> {
+        let builder = RollingFileAppender::builder()
+            .rotation(self.rotation.into())
+            .filename_prefix(&self.file_name_prefix)
+            .filename_suffix("log");
+        let builder = match self.max_files {
+            Some(max_files) => builder.max_log_files(max_files.max(1)),
+            None => builder,
+        };
 
-    trace!("Logger successfully initialized!");
+        builder
+            .build(&self.directory)
+            .map_err(|source| LoggerError::FailedToOpenLogFile {
+                directory: self.directory.clone(),
+                source,
+            })
+    }
+}
+
+/// Masks secrets in the text: bech32 `nsec`/`ncryptsec` keys anywhere and 64-character hex strings
+/// following a secret-like name, e.g. `secret_key=<hex>`, public keys and event ids are kept.
+///
+/// A bare hex secret without such a name is not masked, it can't be told apart from a public key
+/// or an event id, so keys must be kept in [`Secret`](crate::secret::Secret) instead of relying on redaction.
+pub fn redact(text: &str) -> Cow<'_, str> {
+    let text = match BECH32_SECRET.replace_all(text, "${1}1[REDACTED]") {
+        Cow::Borrowed(_) => Cow::Borrowed(text),
+        Cow::Owned(redacted) => Cow::Owned(redacted),
+    };
+    match HEX_SECRET.replace_all(&text, "${1}[REDACTED]") {
+        Cow::Borrowed(_) => text,
+        Cow::Owned(redacted) => Cow::Owned(redacted),
+    }
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(make_writer: M) -> Self {
+        Self(make_writer)
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactingWriter<M::Writer>;
 
-    LoggerGuard {
-        _std_out_guard: std_out_guard,
-        _std_err_guard: std_err_guard,
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingWriter(self.0.make_writer())
     }
+
+    fn make_writer_for(&'a self, meta: &Metadata<'_>) -> Self::Writer {
+        RedactingWriter(self.0.make_writer_for(meta))
+    }
+}
+
+/// Formatting layers write every event with a single call, so a secret is never split between writes.
+impl<W: Write> Write for RedactingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match std::str::from_utf8(buf) {
+            Ok(text) => {
+                self.0.write_all(redact(text).as_bytes())?;
+                Ok(buf.len())
+            }
+            Err(_) => self.0.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Console layers keep default coloring, file layers are written without ANSI escapes.
+fn format_layer<W>(
+    writer: W,
+    format: LogFormat,
+    is_file: bool,
+    filter: impl Filter<Registry> + Send + Sync + 'static,
+) -> BoxedLayer
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    let writer = RedactingMakeWriter::new(writer);
+
+    match format {
+        LogFormat::Text => tracing_fmt::layer()
+            .with_writer(writer)
+            .with_target(false)
+            .with_level(true)
+            .with_ansi(!is_file)
+            .with_filter(filter)
+            .boxed(),
+        LogFormat::Json => json_layer(writer).with_filter(filter).boxed(),
+    }
+}
+
+/// Formats events as single-line JSON objects with timestamp, level, target, fields and entered spans.
+pub fn json_layer<S, W>(make_writer: W) -> tracing_fmt::Layer<S, JsonFields, Format<Json>, W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'writer> MakeWriter<'writer> + 'static,
+{
+    tracing_fmt::layer()
+        .json()
+        .with_writer(make_writer)
+        .with_current_span(false)
+        .with_span_list(true)
+}
+
+pub fn init_logger() -> LoggerGuard {
+    init_logger_with_config(&LoggerConfig::default()).expect("default logger config does not open files")
+}
+
+/// Installs the global logger, console logs go to stdout unless machine output is requested.
+///
+/// Warnings and errors are always mirrored to stderr.
+pub fn init_logger_with_config(config: &LoggerConfig) -> Result<LoggerGuard, LoggerError> {
+    let mut guards = Vec::new();
+    let mut layers: Vec<BoxedLayer> = Vec::new();
+
+    if config.machine_output {
+        let (std_err_writer, std_err_guard) = tracing_appender::non_blocking(io::stderr());
+        guards.push(std_err_guard);
+        layers.push(format_layer(std_err_writer, config.format, false, config.env_filter()));
+    } else {
+        let (std_out_writer, std_out_guard) = tracing_appender::non_blocking(io::stdout());
+        let (std_err_writer, std_err_guard) = tracing_appender::non_blocking(io::stderr());
+        guards.extend([std_out_guard, std_err_guard]);
+        layers.push(format_layer(std_out_writer, config.format, false, config.env_filter()));
+        layers.push(format_layer(std_err_writer, config.format, false, LevelFilter::WARN));
+    }
+
+    if let Some(file) = &config.file {
+        let (file_writer, file_guard) = tracing_appender::non_blocking(file.appender()?);
+        guards.push(file_guard);
+        layers.push(format_layer(file_writer, config.format, true, config.env_filter()));
+    }
+
+    tracing_subscriber::registry().with(layers).init();
+
+    trace!("Logger successfully initialized!");
+
+    Ok(LoggerGuard { _guards: guards })
 }
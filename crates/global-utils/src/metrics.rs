@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{LazyLock, Mutex, PoisonError};
+use std::time::Duration;
+
+/// Buckets in seconds suited for relay round trips.
+pub const DEFAULT_LATENCY_BUCKETS: &[f64] = &[0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+const EXPORTER_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+type Labels = Vec<(String, String)>;
+
+static REGISTRY: LazyLock<Mutex<BTreeMap<&'static str, Family>>> = LazyLock::new(|| Mutex::new(BTreeMap::new()));
+
+/// Monotonic counter, a series is created on first use of every label set.
+#[derive(Debug)]
+pub struct Counter {
+    name: &'static str,
+    help: &'static str,
+}
+
+/// Distribution of observed values over fixed buckets, e.g. request latencies in seconds.
+#[derive(Debug)]
+pub struct Histogram {
+    name: &'static str,
+    help: &'static str,
+    buckets: &'static [f64],
+}
+
+#[derive(Debug, Clone, Default)]
+struct HistogramSeries {
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug)]
+enum Series {
+    Counter(BTreeMap<Labels, u64>),
+    Histogram(&'static [f64], BTreeMap<Labels, HistogramSeries>),
+}
+
+#[derive(Debug)]
+struct Family {
+    help: &'static str,
+    series: Series,
+}
+
+fn to_labels(labels: &[(&str, &str)]) -> Labels {
+    let mut labels = labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect::<Labels>();
+    labels.sort();
+    labels
+}
+
+fn with_registry<T>(f: impl FnOnce(&mut BTreeMap<&'static str, Family>) -> T) -> T {
+    f(&mut REGISTRY.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+impl Counter {
+    pub const fn new(name: &'static str, help: &'static str) -> Self {
+        Self { name, help }
+    }
+
+    pub fn increment(&self, labels: &[(&str, &str)]) {
+        self.add(labels, 1);
+    }
+
+    pub fn add(&self, labels: &[(&str, &str)], value: u64) {
+        with_registry(|registry| {
+            let family = registry.entry(self.name).or_insert_with(|| Family {
+                help: self.help,
+                series: Series::Counter(BTreeMap::new()),
+            });
+            if let Series::Counter(series) = &mut family.series {
+                *series.entry(to_labels(labels)).or_default() += value;
+            }
+        });
+    }
+
+    pub fn get(&self, labels: &[(&str, &str)]) -> u64 {
+        with_registry(|registry| match registry.get(self.name).map(|x| &x.series) {
+            Some(Series::Counter(series)) => series.get(&to_labels(labels)).copied().unwrap_or_default(),
+            _ => 0,
+        })
+    }
+}
+
+impl Histogram {
+    /// Buckets are upper bounds in ascending order, `+Inf` bucket is implicit.
+    pub const fn new(name: &'static str, help: &'static str, buckets: &'static [f64]) -> Self {
+        Self { name, help, buckets }
+    }
+
+    pub fn observe(&self, labels: &[(&str, &str)], value: f64) {
+        with_registry(|registry| {
+            let family = registry.entry(self.name).or_insert_with(|| Family {
+                help: self.help,
+                series: Series::Histogram(self.buckets, BTreeMap::new()),
+            });
+            if let Series::Histogram(buckets, series) = &mut family.series {
+                let series = series.entry(to_labels(labels)).or_insert_with(|| HistogramSeries {
+                    bucket_counts: vec![0; buckets.len()],
+                    ..Default::default()
+                });
+                for (bound, count) in buckets.iter().zip(series.bucket_counts.iter_mut()) {
+                    if value <= *bound {
+                        *count += 1;
+                    }
+                }
+                series.sum += value;
+                series.count += 1;
+            }
+        });
+    }
+
+    pub fn observe_duration(&self, labels: &[(&str, &str)], duration: Duration) {
+        self.observe(labels, duration.as_secs_f64());
+    }
+
+    /// Amount of observed values.
+    pub fn count(&self, labels: &[(&str, &str)]) -> u64 {
+        with_registry(|registry| match registry.get(self.name).map(|x| &x.series) {
+            Some(Series::Histogram(_, series)) => series.get(&to_labels(labels)).map(|x| x.count).unwrap_or_default(),
+            _ => 0,
+        })
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn format_labels(labels: &Labels, extra: Option<(&str, String)>) -> String {
+    let labels = labels
+        .iter()
+        .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+        .chain(extra.map(|(name, value)| format!("{name}=\"{value}\"")))
+        .collect::<Vec<String>>();
+
+    if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels.join(","))
+    }
+}
+
+/// Renders all metrics in the Prometheus text exposition format.
+pub fn render() -> String {
+    with_registry(|registry| {
+        let mut output = String::new();
+        for (name, family) in registry.iter() {
+            match &family.series {
+                Series::Counter(series) => {
+                    let _ = writeln!(output, "# HELP {name} {}\n# TYPE {name} counter", family.help);
+                    for (labels, value) in series {
+                        let _ = writeln!(output, "{name}{} {value}", format_labels(labels, None));
+                    }
+                }
+                Series::Histogram(buckets, series) => {
+                    let _ = writeln!(output, "# HELP {name} {}\n# TYPE {name} histogram", family.help);
+                    for (labels, series) in series {
+                        for (bound, count) in buckets.iter().zip(&series.bucket_counts) {
+                            let labels = format_labels(labels, Some(("le", bound.to_string())));
+                            let _ = writeln!(output, "{name}_bucket{labels} {count}");
+                        }
+                        let inf_labels = format_labels(labels, Some(("le", "+Inf".to_string())));
+                        let _ = writeln!(output, "{name}_bucket{inf_labels} {}", series.count);
+                        let _ = writeln!(output, "{name}_sum{} {}", format_labels(labels, None), series.sum);
+                        let _ = writeln!(output, "{name}_count{} {}", format_labels(labels, None), series.count);
+                    }
+                }
+            }
+        }
+        output
+    })
+}
+
+/// Every request is answered with [`render`] output regardless of its path.
+fn respond(mut stream: TcpStream) -> io::Result<()> {
+    stream.set_read_timeout(Some(EXPORTER_READ_TIMEOUT))?;
+    let mut request = [0u8; 1024];
+    let _ = stream.read(&mut request)?;
+
+    let body = render();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()
+}
+
+/// Serves metrics over HTTP from a background thread, returns the bound address.
+///
+/// Intended for a local port scraped by Prometheus, e.g. `127.0.0.1:9464`, port `0` picks a free one.
+pub fn spawn_exporter(addr: impl ToSocketAddrs) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+
+    std::thread::Builder::new()
+        .name("metrics-exporter".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                let res = stream.and_then(respond);
+                if let Err(err) = res {
+                    tracing::debug!(error = %err, "Failed to serve metrics");
+                }
+            }
+        })?;
+    tracing::info!(addr = %local_addr, "Serving metrics");
+
+    Ok(local_addr)
+}
@@ -0,0 +1,137 @@
+mod tests {
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    use global_utils::logger::{
+        LogFileConfig, LogFormat, LogRotation, LoggerConfig, RedactingMakeWriter, json_layer, redact,
+    };
+    use global_utils::secret::Secret;
+    use tracing_subscriber::fmt::MakeWriter;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().expect("buffer lock").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Buffer;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_logger_config_parsing() -> Result<(), Box<dyn std::error::Error>> {
+        let config = LoggerConfig::from_toml_str(
+            r#"
+            level = "info"
+            format = "json"
+            machine_output = true
+
+            [targets]
+            dex_nostr_relay = "debug"
+            nostr_sdk = "warn"
+
+            [file]
+            directory = "/var/log/dex"
+            rotation = "hourly"
+            max_files = 48
+            "#,
+        )?;
+
+        assert_eq!(config.format, LogFormat::Json);
+        assert!(config.machine_output);
+        assert_eq!(config.directives(), "info,dex_nostr_relay=debug,nostr_sdk=warn");
+        assert_eq!(
+            config.file,
+            Some(LogFileConfig {
+                directory: PathBuf::from("/var/log/dex"),
+                file_name_prefix: "simplicity-dex".to_string(),
+                rotation: LogRotation::Hourly,
+                max_files: Some(48),
+            })
+        );
+
+        assert_eq!(LoggerConfig::from_toml_str("")?, LoggerConfig::default());
+        assert!(LoggerConfig::from_toml_str("format = \"xml\"").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_format() -> Result<(), Box<dyn std::error::Error>> {
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry().with(json_layer(buffer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let _span = tracing::info_span!("publish").entered();
+            tracing::warn!(relay = "wss://relay.example.com", attempt = 2, "Relay rejected event");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().expect("buffer lock").clone())?;
+        let line: serde_json::Value = serde_json::from_str(output.trim())?;
+        assert_eq!(line["level"], "WARN");
+        assert_eq!(line["target"], "test_logger::tests");
+        assert_eq!(line["fields"]["message"], "Relay rejected event");
+        assert_eq!(line["fields"]["relay"], "wss://relay.example.com");
+        assert_eq!(line["fields"]["attempt"], 2);
+        assert_eq!(line["spans"], serde_json::json!([{ "name": "publish" }]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() -> Result<(), Box<dyn std::error::Error>> {
+        let hex = "3501454135014541350145413501453fefb02227e449e57cf4d3a3ce05378683";
+        let nsec = "nsec1j4c6269y9w0q2er2xjw8sv2ehyrtfxq3jwgdlxj6qfn8z4gjsq5qfvfk99";
+
+        assert_eq!(redact(&format!("key: {nsec}")), "key: nsec1[REDACTED]");
+        assert_eq!(
+            redact(
+                "restored ncryptsec1qgg9947rlpvqu76pj5ecreduf9jxhselq2nae2kghhvd5g7dgjtcxfqtd67p9m0w57lspw8gsq6yphnm8623nsl8xn9j4jdzz84zm3frztj3z7s35vpzmqf6ksu8r89qk5z2zxfmu5gv8th8wclt0h4p"
+            ),
+            "restored ncryptsec1[REDACTED]"
+        );
+        assert_eq!(redact(&format!("secret_key={hex}")), "secret_key=[REDACTED]");
+        assert_eq!(
+            redact(&format!("{{\"private_key\":\"{hex}\"}}")),
+            "{\"private_key\":\"[REDACTED]\"}"
+        );
+        assert_eq!(redact(&format!("SecretKey({hex})")), "SecretKey([REDACTED])");
+        // Public keys and event ids are not secrets
+        assert_eq!(redact(&format!("event_id={hex}")), format!("event_id={hex}"));
+        assert_eq!(redact("npub1 pubkey"), "npub1 pubkey");
+
+        let buffer = Buffer::default();
+        let subscriber = tracing_subscriber::registry().with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(RedactingMakeWriter::new(buffer.clone()))
+                .with_ansi(false),
+        );
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(secret_key = hex, "Loaded key {nsec}");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().expect("buffer lock").clone())?;
+        assert!(!output.contains(hex) && !output.contains(nsec), "{output}");
+        assert!(output.contains("Loaded key nsec1[REDACTED]"));
+
+        let secret = Secret::new(nsec.to_string());
+        assert_eq!(format!("{secret:?} {secret}"), "Secret([REDACTED]) [REDACTED]");
+        assert_eq!(secret.expose_secret(), nsec);
+
+        Ok(())
+    }
+}
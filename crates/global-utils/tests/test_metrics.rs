@@ -0,0 +1,57 @@
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    use global_utils::metrics::{Counter, Histogram, render, spawn_exporter};
+
+    static PUBLISHED: Counter = Counter::new("test_events_published_total", "Events accepted by the relay");
+    static FETCH_DURATION: Histogram =
+        Histogram::new("test_fetch_duration_seconds", "Duration of fetches", &[0.1, 1.0]);
+    static EXPORTED: Counter = Counter::new("test_exported_total", "Counter served by the exporter");
+
+    #[test]
+    fn test_text_exposition() {
+        let relay = [("relay", "wss://relay.example.com")];
+        PUBLISHED.increment(&relay);
+        PUBLISHED.add(&relay, 2);
+        PUBLISHED.increment(&[("relay", "wss://\"quoted\".example.com")]);
+        FETCH_DURATION.observe(&relay, 0.05);
+        FETCH_DURATION.observe(&relay, 0.5);
+        FETCH_DURATION.observe(&relay, 3.0);
+
+        assert_eq!(PUBLISHED.get(&relay), 3);
+        assert_eq!(FETCH_DURATION.count(&relay), 3);
+
+        let output = render();
+        for line in [
+            "# TYPE test_events_published_total counter",
+            "test_events_published_total{relay=\"wss://relay.example.com\"} 3",
+            "test_events_published_total{relay=\"wss://\\\"quoted\\\".example.com\"} 1",
+            "# TYPE test_fetch_duration_seconds histogram",
+            "test_fetch_duration_seconds_bucket{relay=\"wss://relay.example.com\",le=\"0.1\"} 1",
+            "test_fetch_duration_seconds_bucket{relay=\"wss://relay.example.com\",le=\"1\"} 2",
+            "test_fetch_duration_seconds_bucket{relay=\"wss://relay.example.com\",le=\"+Inf\"} 3",
+            "test_fetch_duration_seconds_sum{relay=\"wss://relay.example.com\"} 3.55",
+            "test_fetch_duration_seconds_count{relay=\"wss://relay.example.com\"} 3",
+        ] {
+            assert!(output.lines().any(|x| x == line), "missing '{line}' in:\n{output}");
+        }
+    }
+
+    #[test]
+    fn test_exporter_serves_metrics() -> std::io::Result<()> {
+        EXPORTED.increment(&[]);
+        let addr = spawn_exporter("127.0.0.1:0")?;
+
+        let mut stream = TcpStream::connect(addr)?;
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("\r\n\r\n# HELP"));
+        assert!(response.lines().any(|x| x == "test_exported_total 1"));
+
+        Ok(())
+    }
+}